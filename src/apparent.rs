@@ -0,0 +1,167 @@
+use crate::nutation::nutation_matrix;
+use crate::precession::{mean_obliquity, precess_to_date, Epoch};
+use crate::sun::solar_orbit;
+use crate::units::{ARCSEC_TO_DEG, MAS_TO_RAD};
+use crate::vector::{self, Vec3};
+use crate::EquatorialCoords;
+use chrono::{DateTime, Utc};
+
+// constant of aberration in radians (20.49552″)
+const ABERRATION_CONSTANT: f64 = 20.495_52 * ARCSEC_TO_DEG * std::f64::consts::PI / 180.0;
+// Schwarzschild radius of the Sun in AU, scales the gravitational light deflection
+const SUN_SCHWARZSCHILD_RADIUS: f64 = 1.974_125_743_36e-8;
+
+// geocentric unit vector towards the Sun and its distance in AU, mean equator of date
+fn sun_direction(time: DateTime<Utc>) -> (Vec3, f64) {
+    let orbit = solar_orbit(time);
+    let (sin_l, cos_l) = orbit.true_longitude.to_radians().sin_cos();
+    let (sin_e, cos_e) = mean_obliquity(time).to_radians().sin_cos();
+    ([cos_l, sin_l * cos_e, sin_l * sin_e], orbit.distance)
+}
+
+// Earth's orbital velocity as a fraction of the speed of light, mean equator of date
+fn earth_velocity(time: DateTime<Utc>) -> Vec3 {
+    let orbit = solar_orbit(time);
+    let (sin_l, cos_l) = orbit.true_longitude.to_radians().sin_cos();
+    let (sin_p, cos_p) = orbit.perihelion.to_radians().sin_cos();
+    let (sin_e, cos_e) = mean_obliquity(time).to_radians().sin_cos();
+    let x = ABERRATION_CONSTANT * (sin_l - orbit.eccentricity * sin_p);
+    let y = -ABERRATION_CONSTANT * (cos_l - orbit.eccentricity * cos_p);
+    [x, y * cos_e, y * sin_e]
+}
+
+// shifts a catalog direction for a star at the given parallax (mas) to where it is seen from
+// the Earth. The Sun's position is of date, which is close enough to the catalog frame for a
+// correction of at most an arcsecond.
+pub fn annual_parallax(
+    coords: EquatorialCoords,
+    parallax: f64,
+    time: DateTime<Utc>,
+) -> EquatorialCoords {
+    if parallax <= 0.0 {
+        return coords;
+    }
+    let (sun, distance) = sun_direction(time);
+    let p = vector::from_spherical(coords.ra, coords.dec);
+    // the Earth sits at -sun * distance from the Sun
+    let shifted = vector::add_scaled(p, sun, parallax * MAS_TO_RAD * distance);
+    let (ra, dec) = vector::to_spherical(shifted);
+    EquatorialCoords { ra, dec }
+}
+
+// bends a direction away from the Sun (SOFA iauLd with the star at infinity)
+fn deflect(p: Vec3, time: DateTime<Utc>) -> Vec3 {
+    let (sun, distance) = sun_direction(time);
+    let e = [-sun[0], -sun[1], -sun[2]];
+    let q_dot_qe = vector::dot(p, vector::add_scaled(p, e, 1.0));
+    // keeps the correction finite for directions right behind the Sun
+    let limit = 1e-6 / (distance * distance).max(1.0);
+    let w = SUN_SCHWARZSCHILD_RADIUS / distance / q_dot_qe.max(limit);
+    vector::add_scaled(p, vector::cross(p, vector::cross(e, p)), w)
+}
+
+// shifts a direction towards the apex of Earth's motion, first order in v/c
+fn aberrate(p: Vec3, time: DateTime<Utc>) -> Vec3 {
+    vector::normalize(vector::add_scaled(p, earth_velocity(time), 1.0))
+}
+
+// geocentric apparent RA/Dec referred to the true equator and equinox of date: precession,
+// optional light deflection by the Sun, annual aberration and IAU 2000B nutation
+pub fn apparent_place(
+    coords: EquatorialCoords,
+    epoch: Epoch,
+    time: DateTime<Utc>,
+    light_deflection: bool,
+) -> EquatorialCoords {
+    let mean = precess_to_date(coords, epoch, time);
+    let mut p = vector::from_spherical(mean.ra, mean.dec);
+    if light_deflection {
+        p = deflect(p, time);
+    }
+    p = aberrate(p, time);
+    let (ra, dec) = vector::to_spherical(vector::mul_vec(&nutation_matrix(time), p));
+    EquatorialCoords { ra, dec }
+}
+
+// inverse of `apparent_place` for J2000 coordinates, found by iterating the forward
+// transform since the corrections are tiny compared to the position itself
+pub fn catalog_place(
+    apparent: EquatorialCoords,
+    time: DateTime<Utc>,
+    light_deflection: bool,
+) -> EquatorialCoords {
+    let target = vector::from_spherical(apparent.ra, apparent.dec);
+    let mut guess = target;
+    // stops once the forward transform lands within 1e-15 rad of the target
+    for _ in 0..8 {
+        let (ra, dec) = vector::to_spherical(guess);
+        let forward = apparent_place(
+            EquatorialCoords { ra, dec },
+            Epoch::J2000,
+            time,
+            light_deflection,
+        );
+        let reached = vector::from_spherical(forward.ra, forward.dec);
+        let miss = vector::add_scaled(target, reached, -1.0);
+        guess = vector::normalize(vector::add_scaled(guess, miss, 1.0));
+        if vector::dot(miss, miss) < 1e-30 {
+            break;
+        }
+    }
+    let (ra, dec) = vector::to_spherical(guess);
+    EquatorialCoords { ra, dec }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ra_dec_calculations::angular_separation;
+    use crate::time_scales::{self, TimeScale};
+    use chrono::TimeZone;
+
+    #[test]
+    fn theta_persei_matches_meeus_23a() {
+        // the star of Meeus 21.b moved by its proper motion to 2028 November 13.19 TD; the
+        // apparent place is α 2h46m14.390s, δ +49°21′07.45″
+        let reading = Utc.timestamp_opt(1_857_702_816, 0).unwrap();
+        let time = time_scales::from_reading(reading, TimeScale::TT, None).unwrap();
+        let years = 28.867_0;
+        let j2000 = EquatorialCoords {
+            ra: 41.049_942 + 0.034_25 * 15.0 / 3600.0 * years,
+            dec: 49.228_467 - 0.089_5 / 3600.0 * years,
+        };
+        let apparent = apparent_place(j2000, Epoch::J2000, time, false);
+        let expected_ra = (2.0 + 46.0 / 60.0 + 14.390 / 3600.0) * 15.0;
+        let expected_dec: f64 = 49.0 + 21.0 / 60.0 + 7.45 / 3600.0;
+        let ra_error = (apparent.ra - expected_ra) * 3600.0 * expected_dec.to_radians().cos();
+        let dec_error = (apparent.dec - expected_dec) * 3600.0;
+        // Meeus uses IAU 1976 precession and 1980 nutation, which land within 0.1″ of these
+        assert!(
+            ra_error.abs() < 0.15 && dec_error.abs() < 0.15,
+            "{:?}",
+            apparent
+        );
+
+        // the Sun is over 100° away, so deflection adds only milliarcseconds
+        let deflected = apparent_place(j2000, Epoch::J2000, time, true);
+        let shift = angular_separation(apparent, deflected) * 3600.0;
+        assert!(shift > 0.0 && shift < 0.01, "{}", shift);
+    }
+
+    #[test]
+    fn aberration_stays_within_the_constant() {
+        // 20.5″ is the most annual aberration can shift anything
+        let time = Utc.timestamp_opt(1_780_000_000, 0).unwrap();
+        for step in 0..36 {
+            let p = vector::from_spherical(step as f64 * 10.0, 20.0);
+            let (ra, dec) = vector::to_spherical(aberrate(p, time));
+            let shifted = EquatorialCoords { ra, dec };
+            let original = EquatorialCoords {
+                ra: step as f64 * 10.0,
+                dec: 20.0,
+            };
+            let shift = angular_separation(original, shifted) * 3600.0;
+            assert!(shift < 20.6, "{}", shift);
+        }
+    }
+}
@@ -0,0 +1,482 @@
+pub mod astro_obj;
+
+use crate::{
+    check_declination, check_latitude, check_longitude, check_right_ascension, RangeError,
+};
+use std::fmt;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Coord {
+    RA,
+    DEC,
+    LAT,
+    LONG,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    InvalidNumber(String),
+    UnexpectedCharacter(char),
+    MisplacedSign,
+    MissingValue(char),
+    ComponentOrder,
+    TooManyComponents,
+    FractionalComponent,
+    UnitMismatch(char),
+    SignAndHemisphere,
+    MinutesOutOfRange(f64),
+    SecondsOutOfRange(f64),
+    OutOfRange(RangeError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no value found in input"),
+            ParseError::InvalidNumber(num) => write!(f, "'{}' is not a valid number", num),
+            ParseError::UnexpectedCharacter(ch) => write!(f, "unexpected character '{}'", ch),
+            ParseError::MisplacedSign => write!(f, "a sign may only appear at the start"),
+            ParseError::MissingValue(unit) => write!(f, "unit '{}' has no value before it", unit),
+            ParseError::ComponentOrder => {
+                write!(
+                    f,
+                    "components must be in hours/degrees, minutes, seconds order"
+                )
+            }
+            ParseError::TooManyComponents => write!(f, "more than three components given"),
+            ParseError::FractionalComponent => {
+                write!(f, "only the last component may have a fractional part")
+            }
+            ParseError::UnitMismatch(unit) => {
+                write!(f, "unit '{}' can't be used for this coordinate", unit)
+            }
+            ParseError::SignAndHemisphere => {
+                write!(f, "use either a sign or a hemisphere letter, not both")
+            }
+            ParseError::MinutesOutOfRange(mins) => write!(f, "minutes {} must be below 60", mins),
+            ParseError::SecondsOutOfRange(secs) => write!(f, "seconds {} must be below 60", secs),
+            ParseError::OutOfRange(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<RangeError> for ParseError {
+    fn from(err: RangeError) -> ParseError {
+        ParseError::OutOfRange(err)
+    }
+}
+
+enum Token {
+    Separator,
+    Hours,
+    Degrees,
+    Minutes,
+    Seconds,
+}
+
+fn classify(ch: char) -> Result<Token, ParseError> {
+    match ch {
+        ':' => Ok(Token::Separator),
+        ch if ch.is_whitespace() => Ok(Token::Separator),
+        'h' => Ok(Token::Hours),
+        'd' | '°' | 'º' => Ok(Token::Degrees),
+        'm' | '′' | '\'' => Ok(Token::Minutes),
+        's' | '″' | '"' => Ok(Token::Seconds),
+        '+' | '-' | '−' => Err(ParseError::MisplacedSign),
+        _ => Err(ParseError::UnexpectedCharacter(ch)),
+    }
+}
+
+// strips a leading or trailing N/S (latitude) or E/W (longitude) and returns whether it
+// points to the negative hemisphere
+fn strip_hemisphere(input: &str, coord_type: Coord) -> Result<(Option<bool>, &str), ParseError> {
+    let hemisphere = |ch: char| match (ch, coord_type) {
+        ('N', Coord::LAT) | ('E', Coord::LONG) => Ok(Some(false)),
+        ('S', Coord::LAT) | ('W', Coord::LONG) => Ok(Some(true)),
+        ('N', _) | ('S', _) | ('E', _) | ('W', _) => Err(ParseError::UnitMismatch(ch)),
+        _ => Ok(None),
+    };
+
+    if let Some(first) = input.chars().next() {
+        if let Some(negative) = hemisphere(first)? {
+            return Ok((Some(negative), input[first.len_utf8()..].trim()));
+        }
+    }
+    if let Some(last) = input.chars().last() {
+        if let Some(negative) = hemisphere(last)? {
+            return Ok((
+                Some(negative),
+                input[..input.len() - last.len_utf8()].trim(),
+            ));
+        }
+    }
+    Ok((None, input))
+}
+
+// accepts sexagesimal ("05h 34m 31.94s", "-16° 42′ 58″", "05:34:31.94", "22d 00m 52s")
+// as well as partial ("12h", "-00° 30′") and decimal ("5.5", "83.63°") forms.
+// RA is read as hours unless the first component is explicitly marked as degrees.
+// Latitude and longitude may carry a hemisphere letter ("118°14′37″ W") and longitudes
+// are returned east-positive.
+pub fn to_decimal_degrees(input: &str, coord_type: Coord) -> Result<f64, ParseError> {
+    let (hemisphere, trimmed) = strip_hemisphere(input.trim(), coord_type)?;
+    let (negative, body) = match trimmed.chars().next() {
+        Some(sign @ '-') | Some(sign @ '−') => (true, &trimmed[sign.len_utf8()..]),
+        Some('+') => (false, &trimmed[1..]),
+        _ => (false, trimmed),
+    };
+    if hemisphere.is_some() && body.len() != trimmed.len() {
+        return Err(ParseError::SignAndHemisphere);
+    }
+    let negative = hemisphere.unwrap_or(negative);
+
+    // (slot, value, had a fractional part) where slot 0 is hours/degrees, 1 minutes, 2 seconds
+    let mut components: Vec<(usize, f64, bool)> = Vec::new();
+    let mut number = String::new();
+    let mut next_slot = 0;
+    let mut ra_in_degrees = false;
+
+    // a trailing separator flushes the last number
+    for ch in body.chars().chain(std::iter::once(' ')) {
+        if ch.is_ascii_digit() || ch == '.' {
+            number.push(ch);
+            continue;
+        }
+
+        let slot = match classify(ch)? {
+            Token::Separator if number.is_empty() => continue,
+            Token::Separator => next_slot,
+            Token::Hours if coord_type != Coord::RA => return Err(ParseError::UnitMismatch(ch)),
+            Token::Hours => 0,
+            Token::Degrees => {
+                ra_in_degrees = coord_type == Coord::RA;
+                0
+            }
+            Token::Minutes => 1,
+            Token::Seconds => 2,
+        };
+
+        if number.is_empty() {
+            return Err(ParseError::MissingValue(ch));
+        }
+        if slot > 2 {
+            return Err(ParseError::TooManyComponents);
+        }
+        if slot < next_slot {
+            return Err(ParseError::ComponentOrder);
+        }
+
+        let value: f64 = number
+            .parse()
+            .map_err(|_| ParseError::InvalidNumber(number.clone()))?;
+        match slot {
+            1 if value >= 60.0 => return Err(ParseError::MinutesOutOfRange(value)),
+            2 if value >= 60.0 => return Err(ParseError::SecondsOutOfRange(value)),
+            _ => (),
+        }
+        components.push((slot, value, number.contains('.')));
+        next_slot = slot + 1;
+        number.clear();
+    }
+
+    if components.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    if components[..components.len() - 1]
+        .iter()
+        .any(|&(_, _, fractional)| fractional)
+    {
+        return Err(ParseError::FractionalComponent);
+    }
+
+    let magnitude: f64 = components
+        .iter()
+        .map(|&(slot, value, _)| value / 60f64.powi(slot as i32))
+        .sum();
+    let in_degrees = if negative { -magnitude } else { magnitude };
+
+    let checked = match coord_type {
+        Coord::RA if ra_in_degrees => check_right_ascension(in_degrees),
+        Coord::RA => check_right_ascension(in_degrees * 15.0),
+        Coord::DEC => check_declination(in_degrees),
+        Coord::LAT => check_latitude(in_degrees),
+        Coord::LONG => check_longitude(in_degrees),
+    };
+    Ok(checked?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FormatOptions {
+    // digits after the decimal point on the seconds component
+    pub precision: usize,
+    // use d, ' and " in place of °, ′ and ″
+    pub ascii: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions {
+            precision: 2,
+            ascii: false,
+        }
+    }
+}
+
+// splits a non-negative value into whole units, minutes and seconds after rounding to the
+// requested precision so that e.g. 59.999s carries over instead of printing as 60.00s
+fn split_sexagesimal(value: f64, precision: usize) -> (u64, u64, u64, u64) {
+    let scale = 10u64.pow(precision.min(9) as u32);
+    let total = (value * 3600.0 * scale as f64).round() as u64;
+    let secs_scaled = total % (60 * scale);
+    let mins = (total / (60 * scale)) % 60;
+    let whole = total / (3600 * scale);
+    (whole, mins, secs_scaled / scale, secs_scaled % scale)
+}
+
+fn format_seconds(secs: u64, frac: u64, precision: usize) -> String {
+    let precision = precision.min(9);
+    if precision == 0 {
+        return format!("{:02}", secs);
+    }
+    format!("{:02}.{:0width$}", secs, frac, width = precision)
+}
+
+// RA in decimal degrees to "05h 34m 31.94s"
+pub fn format_ra(ra: f64, options: FormatOptions) -> String {
+    let hours = (ra / 15.0).rem_euclid(24.0);
+    let (hours, mins, secs, frac) = split_sexagesimal(hours, options.precision);
+    format!(
+        "{:02}h {:02}m {}s",
+        hours % 24,
+        mins,
+        format_seconds(secs, frac, options.precision)
+    )
+}
+
+// signed angle in decimal degrees to "+22° 00′ 52.20″", used for declination and altitude
+pub fn format_dms(angle: f64, options: FormatOptions) -> String {
+    let (degrees, mins, secs, frac) = split_sexagesimal(angle.abs(), options.precision);
+    // decided after rounding so that tiny negative values print as +00° 00′ 00.00″
+    let rounds_to_zero = (degrees, mins, secs, frac) == (0, 0, 0, 0);
+    let sign = if angle < 0.0 && !rounds_to_zero {
+        '-'
+    } else {
+        '+'
+    };
+    let (deg_mark, min_mark, sec_mark) = unit_marks(options.ascii);
+    format!(
+        "{}{:02}{} {:02}{} {}{}",
+        sign,
+        degrees,
+        deg_mark,
+        mins,
+        min_mark,
+        format_seconds(secs, frac, options.precision),
+        sec_mark
+    )
+}
+
+pub fn format_dec(dec: f64, options: FormatOptions) -> String {
+    format_dms(dec, options)
+}
+
+// azimuth in decimal degrees to "254° 03′ 12.00″", always in [0°, 360°)
+pub fn format_azimuth(azimuth: f64, options: FormatOptions) -> String {
+    let azimuth = azimuth.rem_euclid(360.0);
+    let (degrees, mins, secs, frac) = split_sexagesimal(azimuth, options.precision);
+    let (deg_mark, min_mark, sec_mark) = unit_marks(options.ascii);
+    format!(
+        "{:03}{} {:02}{} {}{}",
+        degrees % 360,
+        deg_mark,
+        mins,
+        min_mark,
+        format_seconds(secs, frac, options.precision),
+        sec_mark
+    )
+}
+
+fn unit_marks(ascii: bool) -> (char, char, char) {
+    if ascii {
+        ('d', '\'', '"')
+    } else {
+        ('°', '′', '″')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees + minutes / 60.0 + seconds / 3600.0
+    }
+
+    #[test]
+    fn parses_every_supported_form() {
+        let cases = [
+            ("05h 34m 31.94s", Coord::RA, dms(5.0, 34.0, 31.94) * 15.0),
+            ("05h34m31.94s", Coord::RA, dms(5.0, 34.0, 31.94) * 15.0),
+            ("05:34:31.94", Coord::RA, dms(5.0, 34.0, 31.94) * 15.0),
+            ("05 34 31.94", Coord::RA, dms(5.0, 34.0, 31.94) * 15.0),
+            ("12h", Coord::RA, 180.0),
+            ("12h 30m", Coord::RA, 187.5),
+            ("5.5", Coord::RA, 82.5),
+            ("83.63°", Coord::RA, 83.63),
+            ("83d 37m 59.1s", Coord::RA, dms(83.0, 37.0, 59.1)),
+            ("+22° 00′ 52.2″", Coord::DEC, dms(22.0, 0.0, 52.2)),
+            ("22d 00m 52s", Coord::DEC, dms(22.0, 0.0, 52.0)),
+            ("22d 00' 52\"", Coord::DEC, dms(22.0, 0.0, 52.0)),
+            ("22º 00′", Coord::DEC, 22.0),
+            ("-16° 42′ 58″", Coord::DEC, -dms(16.0, 42.0, 58.0)),
+            ("−16° 42′ 58″", Coord::DEC, -dms(16.0, 42.0, 58.0)),
+            ("-16d 42' 58\"", Coord::DEC, -dms(16.0, 42.0, 58.0)),
+            ("-16:42:58", Coord::DEC, -dms(16.0, 42.0, 58.0)),
+            ("-00° 30′", Coord::DEC, -0.5),
+            ("-0:30", Coord::DEC, -0.5),
+            ("-00° 00′ 01″", Coord::DEC, -1.0 / 3600.0),
+            ("-12.5", Coord::DEC, -12.5),
+            ("  +45  ", Coord::DEC, 45.0),
+            ("34°03′08″ N", Coord::LAT, dms(34.0, 3.0, 8.0)),
+            ("S 33.9", Coord::LAT, -33.9),
+            ("118° 14′ 37″ W", Coord::LONG, -dms(118.0, 14.0, 37.0)),
+            ("E 2.35", Coord::LONG, 2.35),
+            ("-118.24", Coord::LONG, -118.24),
+        ];
+        for &(input, coord, expected) in &cases {
+            let parsed = to_decimal_degrees(input, coord).unwrap();
+            assert!(
+                (parsed - expected).abs() < 1e-12,
+                "{} gave {}",
+                input,
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn reports_what_is_wrong() {
+        let cases = [
+            ("", Coord::DEC, ParseError::Empty),
+            ("   ", Coord::DEC, ParseError::Empty),
+            ("-", Coord::DEC, ParseError::Empty),
+            (
+                "1.2.3",
+                Coord::DEC,
+                ParseError::InvalidNumber("1.2.3".to_string()),
+            ),
+            ("12x", Coord::DEC, ParseError::UnexpectedCharacter('x')),
+            ("12-30", Coord::DEC, ParseError::MisplacedSign),
+            ("h", Coord::RA, ParseError::MissingValue('h')),
+            ("30m 12h", Coord::RA, ParseError::ComponentOrder),
+            ("1 2 3 4", Coord::DEC, ParseError::TooManyComponents),
+            ("12.5 30", Coord::DEC, ParseError::FractionalComponent),
+            ("12h", Coord::DEC, ParseError::UnitMismatch('h')),
+            ("12 N", Coord::DEC, ParseError::UnitMismatch('N')),
+            ("12 E", Coord::LAT, ParseError::UnitMismatch('E')),
+            ("-12 S", Coord::LAT, ParseError::SignAndHemisphere),
+            ("12 60", Coord::DEC, ParseError::MinutesOutOfRange(60.0)),
+            ("12 30 60", Coord::DEC, ParseError::SecondsOutOfRange(60.0)),
+            (
+                "25h",
+                Coord::RA,
+                ParseError::OutOfRange(RangeError::RightAscension(375.0)),
+            ),
+            (
+                "-95",
+                Coord::DEC,
+                ParseError::OutOfRange(RangeError::Declination(-95.0)),
+            ),
+            (
+                "95 N",
+                Coord::LAT,
+                ParseError::OutOfRange(RangeError::Latitude(95.0)),
+            ),
+            (
+                "181 W",
+                Coord::LONG,
+                ParseError::OutOfRange(RangeError::Longitude(-181.0)),
+            ),
+        ];
+        for (input, coord, expected) in cases.iter() {
+            assert_eq!(
+                to_decimal_degrees(input, *coord).as_ref(),
+                Err(expected),
+                "{}",
+                input
+            );
+        }
+    }
+
+    fn ascii(precision: usize) -> FormatOptions {
+        FormatOptions {
+            precision,
+            ascii: true,
+        }
+    }
+
+    #[test]
+    fn formats_catalog_style() {
+        let m1_ra = dms(5.0, 34.0, 31.94) * 15.0;
+        let m1_dec = dms(22.0, 0.0, 52.2);
+        let options = FormatOptions::default();
+        assert_eq!(format_ra(m1_ra, options), "05h 34m 31.94s");
+        assert_eq!(format_dec(m1_dec, options), "+22° 00′ 52.20″");
+        assert_eq!(format_dec(m1_dec, ascii(1)), "+22d 00' 52.2\"");
+        assert_eq!(format_dms(-0.5, ascii(0)), "-00d 30' 00\"");
+        assert_eq!(format_azimuth(-90.0, options), "270° 00′ 00.00″");
+        // carries instead of printing 60 seconds
+        assert_eq!(format_ra(359.999_999_9, options), "00h 00m 00.00s");
+        assert_eq!(format_dms(29.999_999_9, ascii(2)), "+30d 00' 00.00\"");
+    }
+
+    #[test]
+    fn tiny_negative_values_have_no_minus_sign() {
+        assert_eq!(
+            format_dms(-1e-9, FormatOptions::default()),
+            "+00° 00′ 00.00″"
+        );
+        assert_eq!(format_dms(-0.4 / 3600.0, ascii(0)), "+00d 00' 00\"");
+        assert_eq!(format_dms(-0.6 / 3600.0, ascii(0)), "-00d 00' 01\"");
+        assert_eq!(format_dec(-0.0, ascii(1)), "+00d 00' 00.0\"");
+    }
+
+    #[test]
+    fn formatting_round_trips_to_the_displayed_precision() {
+        for precision in 0..=3 {
+            let options = ascii(precision);
+            // half a unit in the last displayed digit, in degrees
+            let dec_tolerance = 0.5 * 10f64.powi(-(precision as i32)) / 3600.0;
+            let ra_tolerance = dec_tolerance * 15.0;
+
+            for step in 0..2_000 {
+                let ra = step as f64 * 0.179_876_543;
+                let formatted = format_ra(ra, options);
+                let parsed = to_decimal_degrees(&formatted, Coord::RA).unwrap();
+                let error = (parsed - ra + 180.0).rem_euclid(360.0) - 180.0;
+                assert!(
+                    error.abs() <= ra_tolerance + 1e-12,
+                    "{} -> {}",
+                    ra,
+                    formatted
+                );
+                assert_eq!(format_ra(parsed, options), formatted);
+
+                let dec = -90.0 + step as f64 * 0.090_012_345;
+                let formatted = format_dms(dec, options);
+                let parsed = to_decimal_degrees(&formatted, Coord::DEC).unwrap();
+                assert!(
+                    (parsed - dec).abs() <= dec_tolerance + 1e-12,
+                    "{} -> {}",
+                    dec,
+                    formatted
+                );
+                assert_eq!(format_dms(parsed, options), formatted);
+            }
+        }
+    }
+}
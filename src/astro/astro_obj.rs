@@ -0,0 +1,212 @@
+use crate::apparent::{annual_parallax, apparent_place};
+use crate::body::Body;
+use crate::ecliptic::{ecliptic_to_j2000, j2000_to_ecliptic, EclipticFrame};
+use crate::galactic::{
+    equatorial_to_galactic, equatorial_to_supergalactic, galactic_to_equatorial,
+    supergalactic_to_equatorial,
+};
+use crate::observer::Observer;
+use crate::precession::{precess_to_date, to_j2000, Epoch};
+use crate::ra_dec_calculations::{Accuracy, CalculationOptions};
+use crate::space_motion::SpaceMotion;
+use crate::{check_declination, check_right_ascension};
+use crate::{EclipticCoords, EquatorialCoords, GalacticCoords, RangeError, SupergalacticCoords};
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AstroObject<'a> {
+    name: &'a str,
+    right_ascension: f64,
+    declination: f64,
+    epoch: Epoch,
+    motion: SpaceMotion,
+    // when the catalog position was valid, defaults to the instant of `epoch`
+    catalog_epoch: Option<DateTime<Utc>>,
+}
+
+impl<'a> AstroObject<'a> {
+    pub fn new(obj_name: &'a str, right_ascension: f64, declination: f64) -> AstroObject<'a> {
+        AstroObject {
+            name: obj_name,
+            right_ascension,
+            declination,
+            epoch: Epoch::J2000,
+            motion: SpaceMotion::default(),
+            catalog_epoch: None,
+        }
+    }
+
+    pub fn try_new(
+        obj_name: &'a str,
+        right_ascension: f64,
+        declination: f64,
+    ) -> Result<AstroObject<'a>, RangeError> {
+        Ok(AstroObject::new(
+            obj_name,
+            check_right_ascension(right_ascension)?,
+            check_declination(declination)?,
+        ))
+    }
+
+    pub fn from_equatorial(obj_name: &'a str, coords: EquatorialCoords) -> AstroObject<'a> {
+        AstroObject::new(obj_name, coords.ra, coords.dec)
+    }
+
+    // stored as J2000 RA/Dec; `time` only matters for the of-date frames
+    pub fn from_ecliptic(
+        obj_name: &'a str,
+        coords: EclipticCoords,
+        frame: EclipticFrame,
+        time: DateTime<Utc>,
+    ) -> AstroObject<'a> {
+        AstroObject::from_equatorial(obj_name, ecliptic_to_j2000(coords, frame, time))
+    }
+
+    // stored as J2000 RA/Dec
+    pub fn from_galactic(obj_name: &'a str, coords: GalacticCoords) -> AstroObject<'a> {
+        AstroObject::from_equatorial(obj_name, galactic_to_equatorial(coords))
+    }
+
+    pub fn from_supergalactic(obj_name: &'a str, coords: SupergalacticCoords) -> AstroObject<'a> {
+        AstroObject::from_equatorial(obj_name, supergalactic_to_equatorial(coords))
+    }
+
+    // catalog coordinates default to J2000
+    pub fn with_epoch(mut self, epoch: Epoch) -> AstroObject<'a> {
+        self.epoch = epoch;
+        self
+    }
+
+    // mas per year, with RA already multiplied by cos δ
+    pub fn with_proper_motion(mut self, pm_ra_cos_dec: f64, pm_dec: f64) -> AstroObject<'a> {
+        self.motion.pm_ra_cos_dec = pm_ra_cos_dec;
+        self.motion.pm_dec = pm_dec;
+        self
+    }
+
+    // mas
+    pub fn with_parallax(mut self, parallax: f64) -> AstroObject<'a> {
+        self.motion.parallax = parallax;
+        self
+    }
+
+    // km/s, positive when receding
+    pub fn with_radial_velocity(mut self, radial_velocity: f64) -> AstroObject<'a> {
+        self.motion.radial_velocity = radial_velocity;
+        self
+    }
+
+    // for catalogs whose positions are valid at another instant than their equinox, e.g.
+    // Gaia DR3 which is ICRS (J2000) at epoch J2016.0
+    pub fn with_catalog_epoch(mut self, catalog_epoch: DateTime<Utc>) -> AstroObject<'a> {
+        self.catalog_epoch = Some(catalog_epoch);
+        self
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn right_ascension(&self) -> f64 {
+        self.right_ascension
+    }
+
+    pub fn declination(&self) -> f64 {
+        self.declination
+    }
+
+    pub fn equatorial_coords(&self) -> EquatorialCoords {
+        EquatorialCoords {
+            ra: self.right_ascension,
+            dec: self.declination,
+        }
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    pub fn space_motion(&self) -> SpaceMotion {
+        self.motion
+    }
+
+    pub fn catalog_epoch(&self) -> Option<DateTime<Utc>> {
+        self.catalog_epoch.or_else(|| self.epoch.instant())
+    }
+
+    // the catalog position moved by the star's space motion to the given instant, still
+    // referred to the catalog equator and equinox
+    pub fn position_at(&self, time: DateTime<Utc>) -> EquatorialCoords {
+        match self.catalog_epoch() {
+            Some(from) => self.motion.propagate(self.equatorial_coords(), from, time),
+            None => self.equatorial_coords(),
+        }
+    }
+
+    pub fn ecliptic_coords(&self, frame: EclipticFrame, time: DateTime<Utc>) -> EclipticCoords {
+        let j2000 = to_j2000(self.position_at(time), self.epoch, time);
+        j2000_to_ecliptic(j2000, frame, time)
+    }
+
+    // `time` moves the position by its space motion and matters for non-J2000 epochs only
+    pub fn galactic_coords(&self, time: DateTime<Utc>) -> GalacticCoords {
+        equatorial_to_galactic(to_j2000(self.position_at(time), self.epoch, time))
+    }
+
+    pub fn supergalactic_coords(&self, time: DateTime<Utc>) -> SupergalacticCoords {
+        equatorial_to_supergalactic(to_j2000(self.position_at(time), self.epoch, time))
+    }
+
+    // the catalog position precessed to the mean equator and equinox of the given instant
+    pub fn coords_of_date(&self, time: DateTime<Utc>) -> EquatorialCoords {
+        precess_to_date(self.position_at(time), self.epoch, time)
+    }
+
+    // like `apparent_place` but also corrected for space motion and annual parallax
+    pub fn apparent_coords(&self, time: DateTime<Utc>, light_deflection: bool) -> EquatorialCoords {
+        let position = annual_parallax(self.position_at(time), self.motion.parallax, time);
+        apparent_place(position, self.epoch, time, light_deflection)
+    }
+
+    // coordinates of date as used for the horizontal conversion with these options
+    pub fn coords_of_date_with(
+        &self,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> EquatorialCoords {
+        match options.accuracy {
+            Accuracy::Standard => self.coords_of_date(time),
+            Accuracy::High => self.apparent_coords(time, options.light_deflection),
+        }
+    }
+}
+
+impl<'a> Body for AstroObject<'a> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn coords_seen_from(
+        &self,
+        _observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<EquatorialCoords> {
+        Some(self.coords_of_date_with(time, options))
+    }
+}
+
+use std::fmt;
+impl<'a> fmt::Display for AstroObject<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // write out the object name, it's RA, and DEC
+        let options = super::FormatOptions::default();
+        write!(
+            f,
+            "Name: {} \n Right Ascension: {} \n Declination: {} \n",
+            self.name,
+            super::format_ra(self.right_ascension, options),
+            super::format_dec(self.declination, options)
+        )
+    }
+}
@@ -0,0 +1,136 @@
+use crate::observer::Observer;
+use crate::ra_dec_calculations::{
+    equatorial_to_horizontal, equatorial_to_horizontal_details, CalculationOptions,
+    HorizontalDetails,
+};
+use crate::rise_set::{self, RiseSet};
+use crate::{EquatorialCoords, HorizontalCoords};
+use chrono::{DateTime, Duration, Utc};
+
+// anything whose RA/Dec can be worked out for an observer and instant: catalog objects,
+// the Sun, the Moon and the planets. Only `coords_seen_from` has to be provided, the
+// horizontal conversion and event searches are shared. A body whose theory only covers a
+// range of dates has no position outside it, and everything derived from it is None too.
+pub trait Body {
+    fn name(&self) -> &str;
+
+    // RA/Dec of date as seen by the observer, mean or apparent to match `options.accuracy`
+    fn coords_seen_from(
+        &self,
+        observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<EquatorialCoords>;
+
+    // added to the altitude when searching for rise and set, e.g. a semi-diameter so the
+    // event is timed on the upper limb
+    fn limb_offset(&self, _time: DateTime<Utc>) -> f64 {
+        0.0
+    }
+
+    // how far ahead `next_rise` and `next_set` look before giving up and reporting the body
+    // as circumpolar or never rising. Two days suits anything fixed on the sky; bodies that can
+    // stay up or down for longer than that at high latitudes widen it.
+    fn search_window(&self) -> Duration {
+        Duration::hours(rise_set::FIXED_OBJECT_SEARCH_HOURS)
+    }
+
+    // where the body is right now with the default options
+    fn coords_as_alt_az(&self, observer: &Observer) -> Option<HorizontalCoords> {
+        self.coords_as_alt_az_at(observer, Utc::now())
+    }
+
+    fn coords_as_alt_az_at(
+        &self,
+        observer: &Observer,
+        time: DateTime<Utc>,
+    ) -> Option<HorizontalCoords> {
+        self.coords_as_alt_az_with(observer, time, CalculationOptions::default())
+    }
+
+    fn coords_as_alt_az_with(
+        &self,
+        observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<HorizontalCoords> {
+        let coords = self.coords_seen_from(observer, time, options)?;
+        Some(equatorial_to_horizontal(coords, observer, time, options))
+    }
+
+    // alt/az plus hour angle, parallactic angle and airmass for the same instant
+    fn horizontal_details(
+        &self,
+        observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<HorizontalDetails> {
+        let coords = self.coords_seen_from(observer, time, options)?;
+        Some(equatorial_to_horizontal_details(
+            coords, observer, time, options,
+        ))
+    }
+
+    // the horizon is a geometric altitude, like `rise_set::STANDARD_HORIZON`, which already
+    // allows for refraction, so `options.refraction` is ignored here
+    fn next_rise(
+        &self,
+        observer: &Observer,
+        after: DateTime<Utc>,
+        horizon: f64,
+        options: CalculationOptions,
+    ) -> RiseSet {
+        rise_set::next_rise(
+            |time| event_altitude(self, observer, time, options),
+            after,
+            horizon,
+            self.search_window(),
+        )
+    }
+
+    fn next_set(
+        &self,
+        observer: &Observer,
+        after: DateTime<Utc>,
+        horizon: f64,
+        options: CalculationOptions,
+    ) -> RiseSet {
+        rise_set::next_set(
+            |time| event_altitude(self, observer, time, options),
+            after,
+            horizon,
+            self.search_window(),
+        )
+    }
+
+    fn next_transit(
+        &self,
+        observer: &Observer,
+        after: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<DateTime<Utc>> {
+        rise_set::next_transit(
+            |time| {
+                self.horizontal_details(observer, time, options)
+                    .map(|details| details.hour_angle)
+            },
+            after,
+        )
+    }
+}
+
+// what the rise and set searches compare with the horizon: the geometric altitude of the part
+// of the body the event is timed on
+fn event_altitude<B: Body + ?Sized>(
+    body: &B,
+    observer: &Observer,
+    time: DateTime<Utc>,
+    options: CalculationOptions,
+) -> Option<f64> {
+    let geometric = CalculationOptions {
+        refraction: None,
+        ..options
+    };
+    body.coords_as_alt_az_with(observer, time, geometric)
+        .map(|horizontal| horizontal.altitude + body.limb_offset(time))
+}
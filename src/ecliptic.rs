@@ -0,0 +1,166 @@
+use crate::nutation::{nutation_matrix, true_obliquity};
+use crate::precession::{mean_obliquity, precess_to_date, to_j2000, Epoch, J2000_OBLIQUITY};
+use crate::vector;
+use crate::{EclipticCoords, EquatorialCoords};
+use chrono::{DateTime, Utc};
+
+// which ecliptic and equinox a pair of ecliptic coordinates refers to. Each goes with the
+// equator of the same name: J2000 with catalog RA/Dec, mean of date with
+// `AstroObject::coords_of_date` and true of date with the nutated equator.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EclipticFrame {
+    #[default]
+    J2000,
+    MeanOfDate,
+    TrueOfDate,
+}
+
+impl EclipticFrame {
+    // degrees; `time` is ignored for the J2000 ecliptic
+    pub fn obliquity(&self, time: DateTime<Utc>) -> f64 {
+        match self {
+            EclipticFrame::J2000 => J2000_OBLIQUITY,
+            EclipticFrame::MeanOfDate => mean_obliquity(time),
+            EclipticFrame::TrueOfDate => true_obliquity(time),
+        }
+    }
+}
+
+// rotation about the equinox direction by the obliquity (degrees)
+pub fn equatorial_to_ecliptic(coords: EquatorialCoords, obliquity: f64) -> EclipticCoords {
+    let p = vector::from_spherical(coords.ra, coords.dec);
+    let (longitude, latitude) = vector::to_spherical(vector::mul_vec(&vector::rot_x(obliquity), p));
+    EclipticCoords {
+        longitude,
+        latitude,
+    }
+}
+
+pub fn ecliptic_to_equatorial(coords: EclipticCoords, obliquity: f64) -> EquatorialCoords {
+    let p = vector::from_spherical(coords.longitude, coords.latitude);
+    let (ra, dec) = vector::to_spherical(vector::mul_vec(&vector::rot_x(-obliquity), p));
+    EquatorialCoords { ra, dec }
+}
+
+// J2000 catalog coordinates to ecliptic coordinates in the given frame, precessing (and
+// nutating for the true ecliptic) to the instant first
+pub fn j2000_to_ecliptic(
+    coords: EquatorialCoords,
+    frame: EclipticFrame,
+    time: DateTime<Utc>,
+) -> EclipticCoords {
+    let equatorial = match frame {
+        EclipticFrame::J2000 => coords,
+        EclipticFrame::MeanOfDate => precess_to_date(coords, Epoch::J2000, time),
+        EclipticFrame::TrueOfDate => {
+            let mean = precess_to_date(coords, Epoch::J2000, time);
+            let p = vector::from_spherical(mean.ra, mean.dec);
+            let (ra, dec) = vector::to_spherical(vector::mul_vec(&nutation_matrix(time), p));
+            EquatorialCoords { ra, dec }
+        }
+    };
+    equatorial_to_ecliptic(equatorial, frame.obliquity(time))
+}
+
+// inverse of `j2000_to_ecliptic`
+pub fn ecliptic_to_j2000(
+    coords: EclipticCoords,
+    frame: EclipticFrame,
+    time: DateTime<Utc>,
+) -> EquatorialCoords {
+    let equatorial = ecliptic_to_equatorial(coords, frame.obliquity(time));
+    match frame {
+        EclipticFrame::J2000 => equatorial,
+        EclipticFrame::MeanOfDate => to_j2000(equatorial, Epoch::OfDate, time),
+        EclipticFrame::TrueOfDate => {
+            let p = vector::from_spherical(equatorial.ra, equatorial.dec);
+            let p = vector::mul_vec(&vector::transpose(&nutation_matrix(time)), p);
+            let (ra, dec) = vector::to_spherical(p);
+            to_j2000(EquatorialCoords { ra, dec }, Epoch::OfDate, time)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astro::astro_obj::AstroObject;
+    use chrono::TimeZone;
+
+    const POLLUX: EquatorialCoords = EquatorialCoords {
+        ra: 116.328_942,
+        dec: 28.026_183,
+    };
+
+    #[test]
+    fn pollux_matches_meeus_13a() {
+        // with Meeus's J2000 obliquity of 23.439 291 1° (IAU 1980)
+        let ecliptic = equatorial_to_ecliptic(POLLUX, 23.439_291_1);
+        assert!(
+            (ecliptic.longitude - 113.215_630).abs() < 1e-6,
+            "{:?}",
+            ecliptic
+        );
+        assert!(
+            (ecliptic.latitude - 6.684_170).abs() < 1e-6,
+            "{:?}",
+            ecliptic
+        );
+
+        // the IAU 2006 value is 0.042″ smaller, which moves Pollux by a few hundredths of an
+        // arcsecond
+        let time = Utc.timestamp_opt(1_780_000_000, 0).unwrap();
+        let pollux = AstroObject::from_equatorial("Pollux", POLLUX);
+        let ecliptic = pollux.ecliptic_coords(EclipticFrame::J2000, time);
+        assert!(
+            (ecliptic.longitude - 113.215_630).abs() < 2e-5,
+            "{:?}",
+            ecliptic
+        );
+        assert!(
+            (ecliptic.latitude - 6.684_170).abs() < 2e-5,
+            "{:?}",
+            ecliptic
+        );
+    }
+
+    #[test]
+    fn every_frame_round_trips() {
+        let time = Utc.timestamp_opt(1_780_000_000, 0).unwrap();
+        let coords = EclipticCoords {
+            longitude: 113.215_630,
+            latitude: 6.684_170,
+        };
+        for &frame in &[
+            EclipticFrame::J2000,
+            EclipticFrame::MeanOfDate,
+            EclipticFrame::TrueOfDate,
+        ] {
+            let object = AstroObject::from_ecliptic("Pollux", coords, frame, time);
+            let back = object.ecliptic_coords(frame, time);
+            assert!(
+                (back.longitude - coords.longitude).abs() < 1e-10,
+                "{:?}",
+                frame
+            );
+            assert!(
+                (back.latitude - coords.latitude).abs() < 1e-10,
+                "{:?}",
+                frame
+            );
+        }
+
+        // a quarter century of precession moves the equinox about 0.35° along the ecliptic,
+        // and nutation a further few arcseconds
+        let mean = j2000_to_ecliptic(POLLUX, EclipticFrame::MeanOfDate, time);
+        let j2000 = j2000_to_ecliptic(POLLUX, EclipticFrame::J2000, time);
+        assert!(
+            (mean.longitude - j2000.longitude - 0.368).abs() < 0.01,
+            "{:?}",
+            mean
+        );
+        let true_of_date = j2000_to_ecliptic(POLLUX, EclipticFrame::TrueOfDate, time);
+        let nutation = (true_of_date.longitude - mean.longitude) * 3600.0;
+        assert!(nutation.abs() < 20.0 && nutation != 0.0, "{}", nutation);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::vector::{self, Mat3, Vec3};
+use crate::{EquatorialCoords, GalacticCoords, SupergalacticCoords};
+
+// J2000/ICRS equatorial to IAU galactic rotation, the Hipparcos realisation (ESA 1997,
+// vol. 1 §1.5.3): north galactic pole at RA 192.85948°, Dec +27.12825°, with the north
+// celestial pole at l = 122.93192°
+#[rustfmt::skip]
+const EQUATORIAL_TO_GALACTIC: Mat3 = [
+    [-0.054_875_560_416_215_4, -0.873_437_090_234_885, -0.483_835_015_548_713_2],
+    [0.494_109_427_875_583_7, -0.444_829_629_960_011_2, 0.746_982_244_497_219],
+    [-0.867_666_149_019_004_7, -0.198_076_373_431_201_5, 0.455_983_776_175_066_9],
+];
+
+// de Vaucouleurs supergalactic frame in galactic coordinates: north pole at l = 47.37°,
+// b = +6.32° and the zero point of SGL at l = 137.37°, b = 0° (Lahav et al. 2000)
+const SUPERGALACTIC_POLE: (f64, f64) = (47.37, 6.32);
+const SUPERGALACTIC_ORIGIN: (f64, f64) = (137.37, 0.0);
+
+fn galactic_to_supergalactic_matrix() -> Mat3 {
+    let x = vector::from_spherical(SUPERGALACTIC_ORIGIN.0, SUPERGALACTIC_ORIGIN.1);
+    let z = vector::from_spherical(SUPERGALACTIC_POLE.0, SUPERGALACTIC_POLE.1);
+    [x, vector::cross(z, x), z]
+}
+
+fn rotate(long: f64, lat: f64, matrix: &Mat3) -> (f64, f64) {
+    vector::to_spherical(vector::mul_vec(matrix, vector::from_spherical(long, lat)))
+}
+
+fn unrotate(long: f64, lat: f64, matrix: &Mat3) -> (f64, f64) {
+    let p: Vec3 = vector::from_spherical(long, lat);
+    vector::to_spherical(vector::mul_vec(&vector::transpose(matrix), p))
+}
+
+// J2000 catalog RA/Dec to galactic l/b
+pub fn equatorial_to_galactic(coords: EquatorialCoords) -> GalacticCoords {
+    let (longitude, latitude) = rotate(coords.ra, coords.dec, &EQUATORIAL_TO_GALACTIC);
+    GalacticCoords {
+        longitude,
+        latitude,
+    }
+}
+
+pub fn galactic_to_equatorial(coords: GalacticCoords) -> EquatorialCoords {
+    let (ra, dec) = unrotate(coords.longitude, coords.latitude, &EQUATORIAL_TO_GALACTIC);
+    EquatorialCoords { ra, dec }
+}
+
+pub fn galactic_to_supergalactic(coords: GalacticCoords) -> SupergalacticCoords {
+    let matrix = galactic_to_supergalactic_matrix();
+    let (longitude, latitude) = rotate(coords.longitude, coords.latitude, &matrix);
+    SupergalacticCoords {
+        longitude,
+        latitude,
+    }
+}
+
+pub fn supergalactic_to_galactic(coords: SupergalacticCoords) -> GalacticCoords {
+    let matrix = galactic_to_supergalactic_matrix();
+    let (longitude, latitude) = unrotate(coords.longitude, coords.latitude, &matrix);
+    GalacticCoords {
+        longitude,
+        latitude,
+    }
+}
+
+// J2000 catalog RA/Dec to supergalactic SGL/SGB, by way of the galactic frame
+pub fn equatorial_to_supergalactic(coords: EquatorialCoords) -> SupergalacticCoords {
+    galactic_to_supergalactic(equatorial_to_galactic(coords))
+}
+
+pub fn supergalactic_to_equatorial(coords: SupergalacticCoords) -> EquatorialCoords {
+    galactic_to_equatorial(supergalactic_to_galactic(coords))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astro::astro_obj::AstroObject;
+    use crate::body::Body;
+    use crate::observer::Observer;
+    use crate::ra_dec_calculations::CalculationOptions;
+    use crate::GeoCoords;
+    use chrono::{TimeZone, Utc};
+
+    // Sgr A* as given by Reid & Brunthaler (2004)
+    const SGR_A: EquatorialCoords = EquatorialCoords {
+        ra: 266.416_83,
+        dec: -29.007_81,
+    };
+
+    #[test]
+    fn north_celestial_pole_and_galactic_centre() {
+        let pole = equatorial_to_galactic(EquatorialCoords { ra: 0.0, dec: 90.0 });
+        assert!((pole.longitude - 122.931_92).abs() < 1e-5, "{:?}", pole);
+        assert!((pole.latitude - 27.128_25).abs() < 1e-5, "{:?}", pole);
+
+        let centre = equatorial_to_galactic(SGR_A);
+        assert!((centre.longitude - 359.944).abs() < 1e-3, "{:?}", centre);
+        assert!((centre.latitude + 0.046).abs() < 1e-3, "{:?}", centre);
+
+        let back = galactic_to_equatorial(centre);
+        assert!((back.ra - SGR_A.ra).abs() < 1e-10 && (back.dec - SGR_A.dec).abs() < 1e-10);
+    }
+
+    #[test]
+    fn supergalactic_origin() {
+        let origin = supergalactic_to_equatorial(SupergalacticCoords {
+            longitude: 0.0,
+            latitude: 0.0,
+        });
+        assert!((origin.ra - 42.31).abs() < 0.01, "{:?}", origin);
+        assert!((origin.dec - 59.53).abs() < 0.01, "{:?}", origin);
+
+        let back = equatorial_to_supergalactic(origin);
+        assert!(back.longitude.abs() < 1e-10 || (back.longitude - 360.0).abs() < 1e-10);
+        assert!(back.latitude.abs() < 1e-10, "{:?}", back);
+    }
+
+    #[test]
+    fn galactic_objects_give_the_same_horizontal_position() {
+        let observer = Observer::new(GeoCoords::from_west_longitude(-29.26, 70.73).unwrap());
+        let time = Utc.timestamp_opt(1_780_000_000, 0).unwrap();
+        let options = CalculationOptions::high_accuracy();
+        let expected = AstroObject::from_equatorial("Sgr A*", SGR_A)
+            .coords_as_alt_az_with(&observer, time, options)
+            .unwrap();
+
+        let galactic = AstroObject::from_galactic("Sgr A*", equatorial_to_galactic(SGR_A));
+        let supergalactic =
+            AstroObject::from_supergalactic("Sgr A*", equatorial_to_supergalactic(SGR_A));
+        for object in &[galactic, supergalactic] {
+            let seen = object
+                .coords_as_alt_az_with(&observer, time, options)
+                .unwrap();
+            assert!(
+                (seen.altitude - expected.altitude).abs() < 1e-9,
+                "{:?}",
+                seen
+            );
+            assert!((seen.azimuth - expected.azimuth).abs() < 1e-9, "{:?}", seen);
+        }
+    }
+}
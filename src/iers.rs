@@ -0,0 +1,344 @@
+use crate::julian::ModifiedJulianDate;
+use crate::ra_dec_calculations::CalculationOptions;
+use crate::units::ARCSEC_TO_DEG;
+use crate::GeoCoords;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::path::Path;
+
+// within this of a pole longitude is meaningless and the tan(φ) in the longitude shift blows up
+const NEAR_POLE_LATITUDE: f64 = 89.99;
+
+// position of the Celestial Intermediate Pole in the terrestrial frame, arcseconds along the
+// Greenwich meridian (x) and 90° west (y)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PolarMotion {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl PolarMotion {
+    // the observer's latitude and east longitude measured from the instantaneous pole rather
+    // than the conventional one (Explanatory Supplement 3.27), which is all polar motion does
+    // to an alt/az. Right at the poles only the latitude is corrected.
+    pub fn apply(&self, location: GeoCoords) -> GeoCoords {
+        let long = location.east_longitude();
+        let (sin_long, cos_long) = long.to_radians().sin_cos();
+        let (x, y) = (self.x * ARCSEC_TO_DEG, self.y * ARCSEC_TO_DEG);
+        let lat = (location.lat() + x * cos_long - y * sin_long).clamp(-90.0, 90.0);
+        let long_shift = if location.lat().abs() < NEAR_POLE_LATITUDE {
+            (x * sin_long + y * cos_long) * location.lat().to_radians().tan()
+        } else {
+            0.0
+        };
+        // the shift can carry a longitude next to the date line across it
+        let long = (long + long_shift + 180.0).rem_euclid(360.0) - 180.0;
+        GeoCoords::from_east_longitude(lat, long).expect("clamped and wrapped into range")
+    }
+}
+
+// where the values for a date came from, worst last
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EopSource {
+    Observed,
+    Predicted,
+    // outside the table, UT1−UTC and polar motion are taken as zero
+    Default,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EarthOrientation {
+    // seconds
+    pub ut1_utc: f64,
+    pub polar_motion: PolarMotion,
+    pub source: EopSource,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct EopRow {
+    mjd: f64,
+    ut1_utc: f64,
+    polar_motion: PolarMotion,
+    predicted: bool,
+}
+
+#[derive(Debug)]
+pub enum IersError {
+    Io(std::io::Error),
+    // 1-based line number and the column that failed
+    InvalidLine(usize, &'static str),
+    NoData,
+}
+
+impl fmt::Display for IersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IersError::Io(err) => write!(f, "could not read the IERS file: {}", err),
+            IersError::InvalidLine(line, field) => {
+                write!(f, "line {} has an unreadable {} column", line, field)
+            }
+            IersError::NoData => write!(f, "no UT1−UTC values found"),
+        }
+    }
+}
+
+impl std::error::Error for IersError {}
+
+impl From<std::io::Error> for IersError {
+    fn from(err: std::io::Error) -> IersError {
+        IersError::Io(err)
+    }
+}
+
+// fixed-width field by 1-based inclusive columns as in the IERS format description; blank or
+// missing fields are None
+fn field(line: &str, first: usize, last: usize) -> Option<&str> {
+    let value = line.get(first - 1..last.min(line.len()))?.trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some(value)
+}
+
+fn number(
+    line: &str,
+    index: usize,
+    first: usize,
+    last: usize,
+    name: &'static str,
+) -> Result<Option<f64>, IersError> {
+    field(line, first, last)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| IersError::InvalidLine(index + 1, name))
+        })
+        .transpose()
+}
+
+// daily UT1−UTC and polar motion from the IERS rapid service (Bulletin A) in the
+// `finals2000A.all` / `.data` / `.daily` layout, observed values followed by predictions
+#[derive(Clone, Debug, PartialEq)]
+pub struct EopTable {
+    rows: Vec<EopRow>,
+}
+
+impl EopTable {
+    pub fn load(path: impl AsRef<Path>) -> Result<EopTable, IersError> {
+        EopTable::parse(&std::fs::read_to_string(path)?)
+    }
+
+    // rows without a UT1−UTC value (the far end of the predictions) are skipped
+    pub fn parse(contents: &str) -> Result<EopTable, IersError> {
+        let mut rows = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let mjd = match number(line, index, 8, 15, "MJD")? {
+                Some(mjd) if ModifiedJulianDate::from_f64(mjd).to_datetime().is_some() => mjd,
+                Some(_) => return Err(IersError::InvalidLine(index + 1, "MJD")),
+                None => continue,
+            };
+            let ut1_utc = match number(line, index, 59, 68, "UT1-UTC")? {
+                Some(ut1_utc) => ut1_utc,
+                None => continue,
+            };
+            let polar_motion = PolarMotion {
+                x: number(line, index, 19, 27, "PM-x")?.unwrap_or(0.0),
+                y: number(line, index, 38, 46, "PM-y")?.unwrap_or(0.0),
+            };
+            rows.push(EopRow {
+                mjd,
+                ut1_utc,
+                polar_motion,
+                predicted: field(line, 58, 58) == Some("P"),
+            });
+        }
+        if rows.is_empty() {
+            return Err(IersError::NoData);
+        }
+        rows.sort_by(|a, b| a.mjd.total_cmp(&b.mjd));
+        Ok(EopTable { rows })
+    }
+
+    // (first, last) UTC dates covered
+    pub fn range(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let date = |row: &EopRow| {
+            ModifiedJulianDate::from_f64(row.mjd)
+                .to_datetime()
+                .expect("MJDs are checked when parsed")
+        };
+        (date(&self.rows[0]), date(&self.rows[self.rows.len() - 1]))
+    }
+
+    // linear interpolation between the daily values, zero outside the table
+    pub fn at(&self, time: DateTime<Utc>) -> EarthOrientation {
+        let mjd = ModifiedJulianDate::from_datetime(time).to_f64();
+        let after = self.rows.partition_point(|row| row.mjd <= mjd);
+        if after == 0 || (after == self.rows.len() && mjd > self.rows[after - 1].mjd) {
+            return EarthOrientation {
+                ut1_utc: 0.0,
+                polar_motion: PolarMotion::default(),
+                source: EopSource::Default,
+            };
+        }
+        let before = &self.rows[after - 1];
+        let next = self.rows.get(after).unwrap_or(before);
+        let span = next.mjd - before.mjd;
+        let weight = if span > 0.0 {
+            (mjd - before.mjd) / span
+        } else {
+            0.0
+        };
+        let lerp = |a: f64, b: f64| a + (b - a) * weight;
+
+        // a leap second makes UT1−UTC jump by a whole second between two rows; interpolate
+        // the smooth part and keep the step at the start of the next day
+        let step = (next.ut1_utc - before.ut1_utc).round();
+        EarthOrientation {
+            ut1_utc: lerp(before.ut1_utc, next.ut1_utc - step),
+            polar_motion: PolarMotion {
+                x: lerp(before.polar_motion.x, next.polar_motion.x),
+                y: lerp(before.polar_motion.y, next.polar_motion.y),
+            },
+            source: if before.predicted || (weight > 0.0 && next.predicted) {
+                EopSource::Predicted
+            } else {
+                EopSource::Observed
+            },
+        }
+    }
+
+    // the given options with this table's UT1−UTC and polar motion for the instant
+    pub fn options_at(
+        &self,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> CalculationOptions {
+        let orientation = self.at(time);
+        if orientation.source == EopSource::Default {
+            return options;
+        }
+        CalculationOptions {
+            ut1_utc: Some(orientation.ut1_utc),
+            polar_motion: Some(orientation.polar_motion),
+            ..options
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // finals2000A layout around the leap second at the end of 2016; the last row is a prediction
+    const FINALS: &str = "\
+161230 57752.00 I  0.077000 0.000090  0.283000 0.000090  I-0.4079000
+161231 57753.00 I  0.076000 0.000090  0.284000 0.000090  I-0.4086000
+170101 57754.00 I  0.075000 0.000090  0.285000 0.000090  I 0.5913000
+170102 57755.00 P  0.074000 0.000090  0.286000 0.000090  P 0.5906000
+170103 57756.00 P  0.073000 0.000090  0.287000 0.000090
+";
+
+    fn utc(timestamp: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(timestamp, 0).unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn parses_the_fixed_width_columns() {
+        let table = EopTable::parse(FINALS).unwrap();
+        // the row without UT1−UTC is dropped
+        assert_eq!(table.range(), (utc(1_483_056_000), utc(1_483_315_200)));
+
+        let row = table.at(utc(1_483_142_400));
+        assert_close(row.ut1_utc, -0.4086);
+        assert_close(row.polar_motion.x, 0.076);
+        assert_close(row.polar_motion.y, 0.284);
+        assert_eq!(row.source, EopSource::Observed);
+    }
+
+    #[test]
+    fn reports_unreadable_lines() {
+        let bad_ut1 = FINALS.replace("I-0.4086000", "I-0.40x6000");
+        match EopTable::parse(&bad_ut1) {
+            Err(IersError::InvalidLine(2, "UT1-UTC")) => {}
+            other => panic!("{:?}", other),
+        }
+        let bad_x = FINALS.replace("0.075000", "0.07a000");
+        match EopTable::parse(&bad_x) {
+            Err(IersError::InvalidLine(3, "PM-x")) => {}
+            other => panic!("{:?}", other),
+        }
+        match EopTable::parse("\n\n") {
+            Err(IersError::NoData) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn interpolates_between_days() {
+        let table = EopTable::parse(FINALS).unwrap();
+        let noon = table.at(utc(1_483_056_000 + 43_200));
+        assert_close(noon.ut1_utc, -0.40825);
+        assert_close(noon.polar_motion.x, 0.0765);
+        assert_close(noon.polar_motion.y, 0.2835);
+        assert_eq!(noon.source, EopSource::Observed);
+
+        // halfway into the last observed day the next row is already a prediction
+        let predicted = table.at(utc(1_483_228_800 + 43_200));
+        assert_close(predicted.ut1_utc, 0.59095);
+        assert_eq!(predicted.source, EopSource::Predicted);
+    }
+
+    #[test]
+    fn keeps_the_leap_second_step_at_midnight() {
+        let table = EopTable::parse(FINALS).unwrap();
+        // on 31 December only the smooth 0.1 ms drift is interpolated, not the whole second
+        assert_close(table.at(utc(1_483_142_400 + 43_200)).ut1_utc, -0.40865);
+        let just_before = table.at(utc(1_483_228_799)).ut1_utc;
+        assert!((just_before + 0.4087).abs() < 1e-6, "{}", just_before);
+        assert_close(table.at(utc(1_483_228_800)).ut1_utc, 0.5913);
+    }
+
+    #[test]
+    fn defaults_outside_the_table() {
+        let table = EopTable::parse(FINALS).unwrap();
+        let options = CalculationOptions::default();
+        for &time in [utc(1_483_056_000 - 1), utc(1_483_315_200 + 1)].iter() {
+            let outside = table.at(time);
+            assert_eq!(outside.source, EopSource::Default);
+            assert_eq!(outside.ut1_utc, 0.0);
+            assert_eq!(table.options_at(time, options), options);
+        }
+        let inside = table.options_at(utc(1_483_142_400), options);
+        assert_eq!(inside.ut1_utc, Some(-0.4086));
+    }
+
+    #[test]
+    fn polar_motion_stays_finite_at_the_poles() {
+        let motion = PolarMotion { x: 0.2, y: 0.4 };
+        for &lat in [90.0, -90.0, 89.995].iter() {
+            let location = GeoCoords::from_east_longitude(lat, 30.0).unwrap();
+            let moved = motion.apply(location);
+            assert!(moved.lat().abs() <= 90.0, "{:?}", moved);
+            assert_eq!(moved.east_longitude(), 30.0);
+        }
+
+        // away from the poles the longitude shift is (x sin λ + y cos λ) tan φ
+        let moved = motion.apply(GeoCoords::from_east_longitude(45.0, 0.0).unwrap());
+        assert_close(moved.lat(), 45.0 + 0.2 / 3600.0);
+        assert_close(moved.east_longitude(), 0.4 / 3600.0);
+
+        // and a site on the date line stays a valid location
+        let moved = motion.apply(GeoCoords::from_east_longitude(45.0, -180.0).unwrap());
+        assert_close(moved.east_longitude(), 180.0 - 0.4 / 3600.0);
+    }
+}
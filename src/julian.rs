@@ -0,0 +1,447 @@
+use crate::astro::ParseError;
+use crate::time_scales::{self, TimeScale};
+use crate::units::{JULIAN_YEAR, SECONDS_PER_DAY};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+// JD of 1970-01-01 00:00
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+const J2000_JD: f64 = 2_451_545.0;
+const MJD_OFFSET: f64 = 2_400_000.5;
+// B1900.0 and the tropical year, as used for Besselian epochs (Lieske 1979)
+const B1900_JD: f64 = 2_415_020.313_52;
+const TROPICAL_YEAR: f64 = 365.242_198_781;
+
+// whole days and a fraction in [0, 1) kept apart so that sub-millisecond times survive,
+// which a single f64 near 2.4 million days cannot hold
+fn normalize(day: f64, fraction: f64) -> (f64, f64) {
+    let (day, fraction) = (day.trunc(), day.fract() + fraction);
+    let carry = fraction.floor();
+    (day + carry, fraction - carry)
+}
+
+// `day.fraction` with the fraction rounded to `precision` digits, carrying into the day
+fn format_parts(f: &mut fmt::Formatter<'_>, day: f64, fraction: f64) -> fmt::Result {
+    let precision = f.precision().unwrap_or(6).min(15);
+    let scale = 10f64.powi(precision as i32);
+    // the fraction always counts forward, so negative dates print from their magnitude
+    let (mut sign, mut day, fraction) = match (day < 0.0, fraction > 0.0) {
+        (true, true) => ("-", -day - 1.0, 1.0 - fraction),
+        (true, false) => ("-", -day, 0.0),
+        (false, _) => ("", day, fraction),
+    };
+    let mut digits = (fraction * scale).round();
+    if digits >= scale {
+        digits -= scale;
+        day += 1.0;
+    }
+    // tiny negative dates round to zero, which shouldn't print as -0
+    if day == 0.0 && digits == 0.0 {
+        sign = "";
+    }
+    if precision == 0 {
+        return write!(f, "{}{}", sign, day);
+    }
+    write!(
+        f,
+        "{}{}.{:0width$}",
+        sign,
+        day,
+        digits as u64,
+        width = precision
+    )
+}
+
+// "2451545.25", "JD 2451545.25" and "MJD 51544.75" style input; the integer and fractional
+// digits are read separately to keep the two-part precision
+fn parse_parts(input: &str, prefix: &str) -> Result<(f64, f64), ParseError> {
+    let trimmed = input.trim();
+    let body = match trimmed.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => trimmed[prefix.len()..].trim_start(),
+        _ => trimmed,
+    };
+    if body.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    let invalid = || ParseError::InvalidNumber(body.to_string());
+    let (negative, digits) = match body.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, body.strip_prefix('+').unwrap_or(body)),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    let all_digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+    if !all_digits(whole) || !all_digits(fraction) {
+        return Err(invalid());
+    }
+
+    let whole: f64 = if whole.is_empty() {
+        0.0
+    } else {
+        whole.parse().map_err(|_| invalid())?
+    };
+    let fraction: f64 = format!("0.{}0", fraction).parse().map_err(|_| invalid())?;
+    if negative {
+        return Ok(normalize(-whole, -fraction));
+    }
+    Ok(normalize(whole, fraction))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct JulianDate {
+    day: f64,
+    fraction: f64,
+}
+
+impl JulianDate {
+    pub fn new(day: f64, fraction: f64) -> JulianDate {
+        let (day, fraction) = normalize(day, fraction);
+        JulianDate { day, fraction }
+    }
+
+    pub fn from_f64(jd: f64) -> JulianDate {
+        JulianDate::new(jd, 0.0)
+    }
+
+    // the JD of the UTC clock reading; use `at` for JDs on other time scales
+    pub fn from_datetime(time: DateTime<Utc>) -> JulianDate {
+        let days = time.timestamp().div_euclid(86_400);
+        let seconds = time.timestamp().rem_euclid(86_400) as f64
+            + time.timestamp_subsec_nanos() as f64 * 1e-9;
+        JulianDate::new(
+            UNIX_EPOCH_JD.trunc() + days as f64,
+            UNIX_EPOCH_JD.fract() + seconds / SECONDS_PER_DAY,
+        )
+    }
+
+    // the JD on the given scale at a UTC instant, e.g. a TT JD for the ephemeris series.
+    // `ut1_utc` in seconds as in `time_scales`. None when the reading is outside chrono's range.
+    pub fn at(time: DateTime<Utc>, scale: TimeScale, ut1_utc: Option<f64>) -> Option<JulianDate> {
+        time_scales::reading(time, scale, ut1_utc).map(JulianDate::from_datetime)
+    }
+
+    // inverse of `from_datetime`, to the nearest nanosecond. None when the date is outside
+    // what chrono can represent (roughly ±262,000 years).
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        let day_offset = self.day - UNIX_EPOCH_JD.trunc();
+        // written so that NaN and infinities are turned away too
+        if !(day_offset.abs() <= (i64::MAX / 86_400) as f64 && self.fraction.is_finite()) {
+            return None;
+        }
+        let nanos = ((self.fraction - UNIX_EPOCH_JD.fract()) * SECONDS_PER_DAY * 1e9).round();
+        Utc.timestamp_opt(day_offset as i64 * 86_400, 0)
+            .single()?
+            .checked_add_signed(Duration::nanoseconds(nanos as i64))
+    }
+
+    // inverse of `at`: the UTC instant when this JD is reached on the given scale
+    pub fn instant(&self, scale: TimeScale, ut1_utc: Option<f64>) -> Option<DateTime<Utc>> {
+        time_scales::from_reading(self.to_datetime()?, scale, ut1_utc)
+    }
+
+    pub fn day(&self) -> f64 {
+        self.day
+    }
+
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.day + self.fraction
+    }
+
+    pub fn days_since_j2000(&self) -> f64 {
+        (self.day - J2000_JD) + self.fraction
+    }
+
+    pub fn julian_epoch(&self) -> f64 {
+        2000.0 + self.days_since_j2000() / JULIAN_YEAR
+    }
+
+    // e.g. 2016.0 for the Gaia DR3 reference epoch
+    pub fn from_julian_epoch(epoch: f64) -> JulianDate {
+        JulianDate::new(J2000_JD, (epoch - 2000.0) * JULIAN_YEAR)
+    }
+
+    pub fn besselian_epoch(&self) -> f64 {
+        1900.0
+            + ((self.day - B1900_JD.trunc()) + (self.fraction - B1900_JD.fract())) / TROPICAL_YEAR
+    }
+
+    pub fn from_besselian_epoch(epoch: f64) -> JulianDate {
+        JulianDate::new(
+            B1900_JD.trunc(),
+            B1900_JD.fract() + (epoch - 1900.0) * TROPICAL_YEAR,
+        )
+    }
+
+    pub fn to_modified(&self) -> ModifiedJulianDate {
+        ModifiedJulianDate::new(
+            self.day - MJD_OFFSET.trunc(),
+            self.fraction - MJD_OFFSET.fract(),
+        )
+    }
+}
+
+impl From<DateTime<Utc>> for JulianDate {
+    fn from(time: DateTime<Utc>) -> JulianDate {
+        JulianDate::from_datetime(time)
+    }
+}
+
+impl From<ModifiedJulianDate> for JulianDate {
+    fn from(mjd: ModifiedJulianDate) -> JulianDate {
+        mjd.to_julian()
+    }
+}
+
+impl FromStr for JulianDate {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<JulianDate, ParseError> {
+        let (day, fraction) = parse_parts(input, "JD")?;
+        Ok(JulianDate::new(day, fraction))
+    }
+}
+
+// "JD 2451545.000000", `{:.2}` sets the number of decimals
+impl fmt::Display for JulianDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JD ")?;
+        format_parts(f, self.day, self.fraction)
+    }
+}
+
+// JD − 2400000.5, so days start at midnight
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct ModifiedJulianDate {
+    day: f64,
+    fraction: f64,
+}
+
+impl ModifiedJulianDate {
+    pub fn new(day: f64, fraction: f64) -> ModifiedJulianDate {
+        let (day, fraction) = normalize(day, fraction);
+        ModifiedJulianDate { day, fraction }
+    }
+
+    pub fn from_f64(mjd: f64) -> ModifiedJulianDate {
+        ModifiedJulianDate::new(mjd, 0.0)
+    }
+
+    pub fn from_datetime(time: DateTime<Utc>) -> ModifiedJulianDate {
+        JulianDate::from_datetime(time).to_modified()
+    }
+
+    pub fn at(
+        time: DateTime<Utc>,
+        scale: TimeScale,
+        ut1_utc: Option<f64>,
+    ) -> Option<ModifiedJulianDate> {
+        JulianDate::at(time, scale, ut1_utc).map(|jd| jd.to_modified())
+    }
+
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        self.to_julian().to_datetime()
+    }
+
+    pub fn instant(&self, scale: TimeScale, ut1_utc: Option<f64>) -> Option<DateTime<Utc>> {
+        self.to_julian().instant(scale, ut1_utc)
+    }
+
+    pub fn day(&self) -> f64 {
+        self.day
+    }
+
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.day + self.fraction
+    }
+
+    pub fn to_julian(&self) -> JulianDate {
+        JulianDate::new(
+            self.day + MJD_OFFSET.trunc(),
+            self.fraction + MJD_OFFSET.fract(),
+        )
+    }
+}
+
+impl From<DateTime<Utc>> for ModifiedJulianDate {
+    fn from(time: DateTime<Utc>) -> ModifiedJulianDate {
+        ModifiedJulianDate::from_datetime(time)
+    }
+}
+
+impl From<JulianDate> for ModifiedJulianDate {
+    fn from(jd: JulianDate) -> ModifiedJulianDate {
+        jd.to_modified()
+    }
+}
+
+impl FromStr for ModifiedJulianDate {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<ModifiedJulianDate, ParseError> {
+        let (day, fraction) = parse_parts(input, "MJD")?;
+        Ok(ModifiedJulianDate::new(day, fraction))
+    }
+}
+
+impl fmt::Display for ModifiedJulianDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MJD ")?;
+        format_parts(f, self.day, self.fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(timestamp: i64, nanos: u32) -> DateTime<Utc> {
+        Utc.timestamp_opt(timestamp, nanos).unwrap()
+    }
+
+    #[test]
+    fn parses_with_and_without_prefix() {
+        let cases = [
+            ("2451545.25", 2_451_545.0, 0.25),
+            ("JD 2451545.25", 2_451_545.0, 0.25),
+            ("jd2451545.25", 2_451_545.0, 0.25),
+            ("  +2451545  ", 2_451_545.0, 0.0),
+            (".5", 0.0, 0.5),
+            ("-0.5", -1.0, 0.5),
+            ("-1.25", -2.0, 0.75),
+        ];
+        for &(input, day, fraction) in &cases {
+            let jd: JulianDate = input.parse().unwrap();
+            assert_eq!((jd.day(), jd.fraction()), (day, fraction), "{}", input);
+        }
+        let mjd: ModifiedJulianDate = "MJD 60000.125".parse().unwrap();
+        assert_eq!((mjd.day(), mjd.fraction()), (60_000.0, 0.125));
+    }
+
+    #[test]
+    fn keeps_digits_a_single_f64_would_lose() {
+        let jd: JulianDate = "2451545.000000001157".parse().unwrap();
+        assert!((jd.fraction() - 1.157e-9).abs() < 1e-21);
+        assert_eq!(format!("{:.12}", jd), "JD 2451545.000000001157");
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!("".parse::<JulianDate>(), Err(ParseError::Empty));
+        assert_eq!("JD ".parse::<JulianDate>(), Err(ParseError::Empty));
+        for &input in &["2451545.x", "24515e5", "1.2.3", ".", "-", "MJD 51544"] {
+            assert!(
+                matches!(
+                    input.parse::<JulianDate>(),
+                    Err(ParseError::InvalidNumber(_))
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn formats_with_rounding_carry() {
+        assert_eq!(
+            JulianDate::new(2_451_545.0, 0.5).to_string(),
+            "JD 2451545.500000"
+        );
+        assert_eq!(
+            format!("{:.2}", JulianDate::from_f64(2_451_545.999)),
+            "JD 2451546.00"
+        );
+        assert_eq!(
+            format!("{:.0}", JulianDate::from_f64(2_451_545.4)),
+            "JD 2451545"
+        );
+        assert_eq!(
+            ModifiedJulianDate::from_f64(51_544.5).to_string(),
+            "MJD 51544.500000"
+        );
+    }
+
+    #[test]
+    fn formats_negative_dates_from_their_magnitude() {
+        assert_eq!(JulianDate::from_f64(-1.25).to_string(), "JD -1.250000");
+        assert_eq!(JulianDate::from_f64(-3.0).to_string(), "JD -3.000000");
+        assert_eq!(format!("{:.3}", JulianDate::new(-1.0, 1e-7)), "JD -1.000");
+        assert_eq!(
+            format!("{:.3}", JulianDate::new(-1.0, 0.9999999)),
+            "JD 0.000"
+        );
+        for &input in &["-0.5", "-1.25", "-12.000001"] {
+            let jd: JulianDate = input.parse().unwrap();
+            assert_eq!(
+                format!("{:.6}", jd),
+                format!("JD {:.6}", input.parse::<f64>().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn converts_to_and_from_datetime() {
+        let time = utc(946_728_000, 123_456_789);
+        let jd = JulianDate::from_datetime(time);
+        assert_eq!(jd.day(), 2_451_545.0);
+        assert_eq!(jd.to_datetime(), Some(time));
+        assert_eq!(jd.to_modified().to_datetime(), Some(time));
+        assert_eq!(
+            ModifiedJulianDate::from_datetime(utc(-3_506_716_800, 0)).to_f64(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn out_of_range_dates_are_none_instead_of_panicking() {
+        let jd: JulianDate = "99999999999999".parse().unwrap();
+        assert_eq!(jd.to_datetime(), None);
+        assert_eq!(jd.instant(TimeScale::TT, None), None);
+        assert_eq!(JulianDate::from_f64(-1e300).to_datetime(), None);
+        assert_eq!(JulianDate::from_f64(f64::NAN).to_datetime(), None);
+        assert_eq!(JulianDate::from_f64(f64::INFINITY).to_datetime(), None);
+        assert_eq!(JulianDate::new(2_451_545.0, f64::NAN).to_datetime(), None);
+    }
+
+    #[test]
+    fn time_scale_offsets_near_the_limit_are_none() {
+        // chrono stops a little before JD −94,026,000; the ΔT model puts TT years away from
+        // UTC there, so these dates exist on the UTC clock but not once shifted
+        let mut representable = 0;
+        let mut shifted_out = 0;
+        for step in 0..=140 {
+            let jd = JulianDate::from_f64(-94_024_600.0 - step as f64 * 10.0);
+            if let Some(time) = jd.to_datetime() {
+                representable += 1;
+                match jd.instant(TimeScale::TT, None) {
+                    Some(instant) => {
+                        assert!(JulianDate::at(instant, TimeScale::TT, None).is_some())
+                    }
+                    None => shifted_out += 1,
+                }
+                if JulianDate::at(time, TimeScale::TT, None).is_none() {
+                    shifted_out += 1;
+                }
+            }
+        }
+        assert!(representable > 0 && shifted_out > 0);
+    }
+
+    #[test]
+    fn epochs() {
+        assert_eq!(JulianDate::from_julian_epoch(2000.0).to_f64(), J2000_JD);
+        assert!((JulianDate::from_f64(2_433_282.423_5).besselian_epoch() - 1950.0).abs() < 1e-6);
+        let gaia = JulianDate::from_julian_epoch(2016.0);
+        assert_eq!(gaia.to_datetime(), Some(utc(1_451_649_600, 0)));
+    }
+}
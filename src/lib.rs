@@ -0,0 +1,270 @@
+pub mod apparent;
+pub mod astro;
+pub mod body;
+pub mod ecliptic;
+pub mod galactic;
+pub mod iers;
+pub mod julian;
+pub mod moon;
+pub mod nutation;
+pub mod observer;
+pub mod planets;
+pub mod precession;
+pub mod ra_dec_calculations;
+pub mod refraction;
+pub mod rise_set;
+pub mod sidereal;
+pub mod space_motion;
+pub mod sun;
+pub mod time_scales;
+mod units;
+mod vector;
+
+use std::fmt;
+
+// which direction a positive longitude points. Everything in the crate works east-positive
+// internally, so read longitudes through `GeoCoords::east_longitude`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LongitudeConvention {
+    #[default]
+    EastPositive,
+    WestPositive,
+}
+
+// a checked latitude and longitude in degrees. The fields are private so that a longitude
+// can't be read without its sign convention; build one with the constructors below.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoCoords {
+    lat: f64,
+    long: f64,
+    convention: LongitudeConvention,
+}
+
+impl GeoCoords {
+    // longitude is east-positive
+    pub fn try_new(lat: f64, long: f64) -> Result<GeoCoords, RangeError> {
+        GeoCoords::from_east_longitude(lat, long)
+    }
+
+    pub fn from_east_longitude(lat: f64, long: f64) -> Result<GeoCoords, RangeError> {
+        Ok(GeoCoords {
+            lat: check_latitude(lat)?,
+            long: check_longitude(long)?,
+            convention: LongitudeConvention::EastPositive,
+        })
+    }
+
+    // e.g. Los Angeles at 118.24° W is `from_west_longitude(34.05, 118.24)`
+    pub fn from_west_longitude(lat: f64, long: f64) -> Result<GeoCoords, RangeError> {
+        Ok(GeoCoords {
+            lat: check_latitude(lat)?,
+            long: check_longitude(long)?,
+            convention: LongitudeConvention::WestPositive,
+        })
+    }
+
+    // parses strings like "34°03′08″ N" and "118°14′37″ W"; unmarked values are east-positive
+    pub fn parse(lat: &str, long: &str) -> Result<GeoCoords, astro::ParseError> {
+        let lat = astro::to_decimal_degrees(lat, astro::Coord::LAT)?;
+        let long = astro::to_decimal_degrees(long, astro::Coord::LONG)?;
+        Ok(GeoCoords::from_east_longitude(lat, long)?)
+    }
+
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    // the longitude as it was given, to be read together with `convention`
+    pub fn long(&self) -> f64 {
+        self.long
+    }
+
+    pub fn convention(&self) -> LongitudeConvention {
+        self.convention
+    }
+
+    pub fn east_longitude(&self) -> f64 {
+        match self.convention {
+            LongitudeConvention::EastPositive => self.long,
+            LongitudeConvention::WestPositive => -self.long,
+        }
+    }
+}
+
+// right ascension and declination, both in decimal degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EquatorialCoords {
+    pub ra: f64,
+    pub dec: f64,
+}
+
+// ecliptic longitude and latitude in degrees, see `ecliptic::EclipticFrame` for the frames
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EclipticCoords {
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+// galactic longitude l and latitude b in degrees (IAU system)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GalacticCoords {
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+// supergalactic longitude SGL and latitude SGB in degrees (de Vaucouleurs)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SupergalacticCoords {
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+// altitude above the horizon and azimuth measured from north through east, in degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HorizontalCoords {
+    pub altitude: f64,
+    pub azimuth: f64,
+}
+
+// each variant carries the offending value in degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RangeError {
+    RightAscension(f64),
+    Declination(f64),
+    Latitude(f64),
+    Longitude(f64),
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::RightAscension(ra) => {
+                write!(f, "right ascension {}° is outside [0°, 360°)", ra)
+            }
+            RangeError::Declination(dec) => {
+                write!(f, "declination {}° is outside [-90°, 90°]", dec)
+            }
+            RangeError::Latitude(lat) => write!(f, "latitude {}° is outside [-90°, 90°]", lat),
+            RangeError::Longitude(long) => {
+                write!(f, "longitude {}° is outside [-180°, 180°]", long)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+// the range checks below also reject NaN since it is never contained in a range
+pub fn check_right_ascension(ra: f64) -> Result<f64, RangeError> {
+    if (0.0..360.0).contains(&ra) {
+        return Ok(ra);
+    }
+    Err(RangeError::RightAscension(ra))
+}
+
+pub fn check_declination(dec: f64) -> Result<f64, RangeError> {
+    if (-90.0..=90.0).contains(&dec) {
+        return Ok(dec);
+    }
+    Err(RangeError::Declination(dec))
+}
+
+pub fn check_latitude(lat: f64) -> Result<f64, RangeError> {
+    if (-90.0..=90.0).contains(&lat) {
+        return Ok(lat);
+    }
+    Err(RangeError::Latitude(lat))
+}
+
+pub fn check_longitude(long: f64) -> Result<f64, RangeError> {
+    if (-180.0..=180.0).contains(&long) {
+        return Ok(long);
+    }
+    Err(RangeError::Longitude(long))
+}
+
+impl fmt::Display for EquatorialCoords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = astro::FormatOptions::default();
+        write!(
+            f,
+            "RA {}, Dec {}",
+            astro::format_ra(self.ra, options),
+            astro::format_dec(self.dec, options)
+        )
+    }
+}
+
+impl fmt::Display for EclipticCoords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = astro::FormatOptions::default();
+        write!(
+            f,
+            "Lon {}, Lat {}",
+            astro::format_azimuth(self.longitude, options),
+            astro::format_dms(self.latitude, options)
+        )
+    }
+}
+
+impl fmt::Display for GalacticCoords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = astro::FormatOptions::default();
+        write!(
+            f,
+            "l {}, b {}",
+            astro::format_azimuth(self.longitude, options),
+            astro::format_dms(self.latitude, options)
+        )
+    }
+}
+
+impl fmt::Display for SupergalacticCoords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = astro::FormatOptions::default();
+        write!(
+            f,
+            "SGL {}, SGB {}",
+            astro::format_azimuth(self.longitude, options),
+            astro::format_dms(self.latitude, options)
+        )
+    }
+}
+
+impl fmt::Display for HorizontalCoords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = astro::FormatOptions::default();
+        write!(
+            f,
+            "Alt {}, Az {}",
+            astro::format_dms(self.altitude, options),
+            astro::format_azimuth(self.azimuth, options)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geo_coords_are_checked_and_read_east_positive() {
+        let los_angeles = GeoCoords::from_west_longitude(34.05, 118.24).unwrap();
+        assert_eq!(los_angeles.lat(), 34.05);
+        assert_eq!(los_angeles.east_longitude(), -118.24);
+        assert_eq!(los_angeles.convention(), LongitudeConvention::WestPositive);
+
+        let parsed = GeoCoords::parse("34° 03′ N", "118° 14.4′ W").unwrap();
+        assert!((parsed.east_longitude() + 118.24).abs() < 1e-12);
+
+        assert_eq!(
+            GeoCoords::from_east_longitude(91.0, 0.0),
+            Err(RangeError::Latitude(91.0))
+        );
+        assert_eq!(
+            GeoCoords::from_west_longitude(0.0, 181.0),
+            Err(RangeError::Longitude(181.0))
+        );
+        assert!(GeoCoords::parse("95 N", "0").is_err());
+    }
+}
@@ -1,3 +1,28 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+use ra_dec_to_alt_az::astro::{self, astro_obj::AstroObject};
+use ra_dec_to_alt_az::body::Body;
+use ra_dec_to_alt_az::observer::{LocalOffset, Observer};
+use ra_dec_to_alt_az::GeoCoords;
+
+// US Pacific time under the rules in force since 2007: daylight saving from 2am on the second
+// Sunday in March to 2am on the first Sunday in November. chrono-tz's `America::Los_Angeles`
+// would do the same job
+#[derive(Debug)]
+struct Pacific;
+
+impl LocalOffset for Pacific {
+    fn offset_at(&self, time: DateTime<Utc>) -> FixedOffset {
+        let first_sunday_from = |month, day| {
+            let date = NaiveDate::from_ymd_opt(time.year(), month, day).unwrap();
+            date + Duration::days(((7 - date.weekday().num_days_from_sunday()) % 7) as i64)
+        };
+        let starts = Utc.from_utc_datetime(&first_sunday_from(3, 8).and_hms_opt(10, 0, 0).unwrap());
+        let ends = Utc.from_utc_datetime(&first_sunday_from(11, 1).and_hms_opt(9, 0, 0).unwrap());
+        let hours = if time >= starts && time < ends { 7 } else { 8 };
+        FixedOffset::west_opt(hours * 3600).unwrap()
+    }
+}
+
 fn main() {
     // use helper funcs to get ra-dec strs into decimal degrees
     let m1_ra = match astro::to_decimal_degrees("05h 34m 31.94s", astro::Coord::RA) {
@@ -10,129 +35,29 @@ fn main() {
         Err(e) => panic!("An error occured when parsing declination: {:?}", e),
     };
 
-    let m1 = astro::astro_obj::AstroObject::new("M1 Crab Nebula (Supernova Remnant)", m1_ra, m1_dec);
-
-    let location = GeoCoords {
-        lat: 34.0522,
-        long: 118.2437,
+    let m1 = match AstroObject::try_new("M1 Crab Nebula (Supernova Remnant)", m1_ra, m1_dec) {
+        Ok(obj) => obj,
+        Err(e) => panic!("An error occured when creating the object: {}", e),
     };
-    println!("{}", m1);
-    println!("{:?}", m1.coords_as_alt_az(location));
-}
-
-pub struct GeoCoords {
-    pub lat: f32,
-    pub long: f32,
-}
-
-mod astro {
-    pub mod astro_obj {
-        pub struct AstroObject<'a> {
-            name: &'a str,
-            right_ascension: f32,
-            declination: f32,
-        }
-
-        impl<'a> AstroObject<'a> {
-            pub fn new(obj_name: &str, right_ascension: f32, declination: f32) -> AstroObject {
-                AstroObject {
-                    name: obj_name,
-                    right_ascension: right_ascension,
-                    declination: declination,
-                }
-            }
-
-            pub fn coords_as_alt_az(&self, location_info: crate::GeoCoords) -> (f32, f32) {
-                use crate::ra_dec_calculations::*;
-                let days_j2000 = calculate_days_since_j2000();
-                let local_sidereal_time =
-                    calculate_local_sidereal_time(days_j2000, location_info.long);
-                let mut hour_angle = local_sidereal_time - self.right_ascension;
-                if hour_angle < 0.0 {
-                    hour_angle += 360.0
-                };
-                let alt_az = calculate_alt_az(hour_angle, self.declination, location_info);
-                alt_az
-            }
-        }
-
-        use std::fmt;
-        impl<'a> fmt::Display for AstroObject<'a> {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                // write out the object name, it's RA, and DEC
-                write!(
-                    f,
-                    "Name: {} \n Right Ascension: {} \n Declination: {} \n",
-                    self.name, self.right_ascension, self.declination
-                )
-            }
-        }
-    }
-
-    #[derive(PartialEq)]
-    pub enum Coord {
-        RA,
-        DEC,
-    }
-
-    use std::num::ParseFloatError;
-    pub fn to_decimal_degrees(input: &str, coord_type: Coord) -> Result<f32, ParseFloatError> {
-        let tokens: Vec<_> = input
-            .split(&[' ', 'h', 'm', 's', '°', '′', '+', '-', '″'][..])
-            .filter(|ch| !ch.is_empty())
-            .collect();
 
-        let hours_or_degrees: f32 = tokens[0].parse()?;
-        let mins: f32 = tokens[1].parse()?;
-        let secs: f32 = tokens[2].parse()?;
-
-        let in_degrees = hours_or_degrees + (mins / 60.0) + (secs / 3600.0);
-
-        if coord_type == Coord::RA {
-            return Ok(in_degrees * 15.0);
-        }
-
-        Ok(in_degrees)
-    }
-}
-
-mod ra_dec_calculations {
-    use chrono::prelude::*;
-
-    pub fn calculate_days_since_j2000() -> f32 {
-        let j2000 = Utc.ymd(2000, 1, 1).and_hms(12, 0, 0);
-        let now = Utc::now();
-        let days_since = (now - j2000).num_seconds() as f32 / (24.0 * 3600.0);
-        days_since
-    }
-
-    pub fn calculate_local_sidereal_time(days_j2000: f32, long: f32) -> f32 {
-        let now = Utc::now();
-        let fraction_of_hour = now.minute() as f32 / 60.0;
-        let ut = now.hour() as f32 + fraction_of_hour;
-        // this is an approximate formula for local sidereal time taken from linked article. See readme.md
-        let local_siderial_time =
-            (100.46 + 0.985647 * days_j2000 + long + 15.0 * ut + 360.0) % 360.0;
-        local_siderial_time
-    }
-
-    pub fn calculate_alt_az(ha: f32, dec: f32, location: super::GeoCoords) -> (f32, f32) {
-        let prelim_alt = (dec.to_radians().sin() * location.lat.to_radians().sin())
-            + (dec.to_radians().cos() * location.lat.to_radians().cos() * ha.to_radians().cos());
-
-        let alt = prelim_alt.asin().to_degrees();
-
-        let prelim_az = (dec.to_radians().sin()
-            - (alt.to_radians().sin() * location.lat.to_radians().sin()))
-            / (alt.to_radians().cos() * location.lat.to_radians().cos());
-
-        let prelim_az = prelim_az.acos().to_degrees();
+    let location = match GeoCoords::parse("34° 03′ 08″ N", "118° 14′ 37″ W") {
+        Ok(location) => location,
+        Err(e) => panic!("An error occured when setting the observer location: {}", e),
+    };
+    let observer = Observer::new(location)
+        .with_name("Los Angeles")
+        .with_height(89.0)
+        .with_timezone(&Pacific);
 
-        if ha.to_radians().sin().to_degrees() < 0.0 {
-            let az = prelim_az;
-            return (az, alt);
-        }
-        let az = 360.0 - prelim_az;
-        (alt, az)
+    let now = Utc::now();
+    println!("{}", m1);
+    println!(
+        "From {} at {}:",
+        observer.name.unwrap_or("observer"),
+        observer.local_time(now).format("%Y-%m-%d %H:%M:%S %:z")
+    );
+    match m1.coords_as_alt_az_at(&observer, now) {
+        Some(horizontal) => println!("{}", horizontal),
+        None => println!("no position for {}", now),
     }
 }
@@ -0,0 +1,458 @@
+use crate::astro::astro_obj::AstroObject;
+use crate::body::Body;
+use crate::ecliptic::ecliptic_to_equatorial;
+use crate::nutation::{nutation, true_obliquity};
+use crate::observer::Observer;
+use crate::precession::mean_obliquity;
+use crate::ra_dec_calculations::{
+    angular_separation, calculate_days_since_j2000, Accuracy, CalculationOptions,
+};
+use crate::sidereal::local_sidereal_time;
+use crate::sun::{solar_orbit, Sun};
+use crate::units::AU_KM;
+use crate::{EclipticCoords, EquatorialCoords};
+use chrono::{DateTime, Duration, Utc};
+
+const EARTH_RADIUS_KM: f64 = 6_378.14;
+// ratio of the Moon's radius to the Earth's equatorial radius
+const MOON_RADIUS_RATIO: f64 = 0.272_5;
+
+// Meeus table 47.A: multiples of D, M, M' and F, then the longitude (1e-6 degree) and
+// distance (1e-3 km) coefficients
+#[rustfmt::skip]
+const LONGITUDE_DISTANCE: [([i8; 4], i32, i32); 60] = [
+    ([0, 0, 1, 0], 6288774, -20905355),
+    ([2, 0, -1, 0], 1274027, -3699111),
+    ([2, 0, 0, 0], 658314, -2955968),
+    ([0, 0, 2, 0], 213618, -569925),
+    ([0, 1, 0, 0], -185116, 48888),
+    ([0, 0, 0, 2], -114332, -3149),
+    ([2, 0, -2, 0], 58793, 246158),
+    ([2, -1, -1, 0], 57066, -152138),
+    ([2, 0, 1, 0], 53322, -170733),
+    ([2, -1, 0, 0], 45758, -204586),
+    ([0, 1, -1, 0], -40923, -129620),
+    ([1, 0, 0, 0], -34720, 108743),
+    ([0, 1, 1, 0], -30383, 104755),
+    ([2, 0, 0, -2], 15327, 10321),
+    ([0, 0, 1, 2], -12528, 0),
+    ([0, 0, 1, -2], 10980, 79661),
+    ([4, 0, -1, 0], 10675, -34782),
+    ([0, 0, 3, 0], 10034, -23210),
+    ([4, 0, -2, 0], 8548, -21636),
+    ([2, 1, -1, 0], -7888, 24208),
+    ([2, 1, 0, 0], -6766, 30824),
+    ([1, 0, -1, 0], -5163, -8379),
+    ([1, 1, 0, 0], 4987, -16675),
+    ([2, -1, 1, 0], 4036, -12831),
+    ([2, 0, 2, 0], 3994, -10445),
+    ([4, 0, 0, 0], 3861, -11650),
+    ([2, 0, -3, 0], 3665, 14403),
+    ([0, 1, -2, 0], -2689, -7003),
+    ([2, 0, -1, 2], -2602, 0),
+    ([2, -1, -2, 0], 2390, 10056),
+    ([1, 0, 1, 0], -2348, 6322),
+    ([2, -2, 0, 0], 2236, -9884),
+    ([0, 1, 2, 0], -2120, 5751),
+    ([0, 2, 0, 0], -2069, 0),
+    ([2, -2, -1, 0], 2048, -4950),
+    ([2, 0, 1, -2], -1773, 4130),
+    ([2, 0, 0, 2], -1595, 0),
+    ([4, -1, -1, 0], 1215, -3958),
+    ([0, 0, 2, 2], -1110, 0),
+    ([3, 0, -1, 0], -892, 3258),
+    ([2, 1, 1, 0], -810, 2616),
+    ([4, -1, -2, 0], 759, -1897),
+    ([0, 2, -1, 0], -713, -2117),
+    ([2, 2, -1, 0], -700, 2354),
+    ([2, 1, -2, 0], 691, 0),
+    ([2, -1, 0, -2], 596, 0),
+    ([4, 0, 1, 0], 549, -1423),
+    ([0, 0, 4, 0], 537, -1117),
+    ([4, -1, 0, 0], 520, -1571),
+    ([1, 0, -2, 0], -487, -1739),
+    ([2, 1, 0, -2], -399, 0),
+    ([0, 0, 2, -2], -381, -4421),
+    ([1, 1, 1, 0], 351, 0),
+    ([3, 0, -2, 0], -340, 0),
+    ([4, 0, -3, 0], 330, 0),
+    ([2, -1, 2, 0], 327, 0),
+    ([0, 2, 1, 0], -323, 1165),
+    ([1, 1, -1, 0], 299, 0),
+    ([2, 0, 3, 0], 294, 0),
+    ([2, 0, -1, -2], 0, 8752),
+];
+
+// Meeus table 47.B: multiples of D, M, M' and F, then the latitude coefficient (1e-6 degree)
+#[rustfmt::skip]
+const LATITUDE: [([i8; 4], i32); 60] = [
+    ([0, 0, 0, 1], 5128122),
+    ([0, 0, 1, 1], 280602),
+    ([0, 0, 1, -1], 277693),
+    ([2, 0, 0, -1], 173237),
+    ([2, 0, -1, 1], 55413),
+    ([2, 0, -1, -1], 46271),
+    ([2, 0, 0, 1], 32573),
+    ([0, 0, 2, 1], 17198),
+    ([2, 0, 1, -1], 9266),
+    ([0, 0, 2, -1], 8822),
+    ([2, -1, 0, -1], 8216),
+    ([2, 0, -2, -1], 4324),
+    ([2, 0, 1, 1], 4200),
+    ([2, 1, 0, -1], -3359),
+    ([2, -1, -1, 1], 2463),
+    ([2, -1, 0, 1], 2211),
+    ([2, -1, -1, -1], 2065),
+    ([0, 1, -1, -1], -1870),
+    ([4, 0, -1, -1], 1828),
+    ([0, 1, 0, 1], -1794),
+    ([0, 0, 0, 3], -1749),
+    ([0, 1, -1, 1], -1565),
+    ([1, 0, 0, 1], -1491),
+    ([0, 1, 1, 1], -1475),
+    ([0, 1, 1, -1], -1410),
+    ([0, 1, 0, -1], -1344),
+    ([1, 0, 0, -1], -1335),
+    ([0, 0, 3, 1], 1107),
+    ([4, 0, 0, -1], 1021),
+    ([4, 0, -1, 1], 833),
+    ([0, 0, 1, -3], 777),
+    ([4, 0, -2, 1], 671),
+    ([2, 0, 0, -3], 607),
+    ([2, 0, 2, -1], 596),
+    ([2, -1, 1, -1], 491),
+    ([2, 0, -2, 1], -451),
+    ([0, 0, 3, -1], 439),
+    ([2, 0, 2, 1], 422),
+    ([2, 0, -3, -1], 421),
+    ([2, 1, -1, 1], -366),
+    ([2, 1, 0, 1], -351),
+    ([4, 0, 0, 1], 331),
+    ([2, -1, 1, 1], 315),
+    ([2, -2, 0, -1], 302),
+    ([0, 0, 1, 3], -283),
+    ([2, 1, 1, -1], -229),
+    ([1, 1, 0, -1], 223),
+    ([1, 1, 0, 1], 223),
+    ([0, 1, -2, -1], -220),
+    ([2, 1, -1, -1], -220),
+    ([1, 0, 1, 1], -185),
+    ([2, -1, -2, -1], 181),
+    ([0, 1, 2, 1], -177),
+    ([4, 0, -2, -1], 176),
+    ([4, -1, -1, -1], 166),
+    ([1, 0, 1, -1], -164),
+    ([4, 0, 1, -1], 132),
+    ([1, 0, -1, -1], -119),
+    ([4, -1, 0, -1], 115),
+    ([2, -2, 0, 1], 107),
+];
+
+// geocentric ecliptic position referred to the mean equinox of date, degrees and km
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LunarPosition {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub distance: f64,
+}
+
+// the Moon from the truncated ELP-2000/82 series in Meeus ch. 47, good to about 10″ in
+// longitude and 4″ in latitude
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Moon;
+
+impl Moon {
+    pub fn ecliptic_position(&self, time: DateTime<Utc>) -> LunarPosition {
+        let t = calculate_days_since_j2000(time) / 36_525.0;
+        let mean_longitude = 218.316_447_7
+            + t * (481_267.881_234_21
+                + t * (-0.001_578_6 + t * (1.0 / 538_841.0 - t / 65_194_000.0)));
+        let elongation = 297.850_192_1
+            + t * (445_267.111_403_4
+                + t * (-0.001_881_9 + t * (1.0 / 545_868.0 - t / 113_065_000.0)));
+        let sun_anomaly =
+            357.529_109_2 + t * (35_999.050_290_9 + t * (-0.000_153_6 + t / 24_490_000.0));
+        let moon_anomaly = 134.963_396_4
+            + t * (477_198.867_505_5 + t * (0.008_741_4 + t * (1.0 / 69_699.0 - t / 14_712_000.0)));
+        let node_distance = 93.272_095
+            + t * (483_202.017_523_3
+                + t * (-0.003_653_9 + t * (-1.0 / 3_526_000.0 + t / 863_310_000.0)));
+        let a1 = 119.75 + 131.849 * t;
+        let a2 = 53.09 + 479_264.29 * t;
+        let a3 = 313.45 + 481_266.484 * t;
+        // the Earth's orbital eccentricity shrinks the terms involving the Sun's anomaly
+        let e = 1.0 - t * (0.002_516 + t * 0.000_007_4);
+
+        let args = [elongation, sun_anomaly, moon_anomaly, node_distance];
+        let argument = |multiples: &[i8; 4]| -> (f64, f64) {
+            let angle: f64 = multiples
+                .iter()
+                .zip(args.iter())
+                .map(|(&n, &a)| n as f64 * a)
+                .sum();
+            (angle.to_radians(), e.powi(multiples[1].abs() as i32))
+        };
+
+        let (mut sum_l, mut sum_r, mut sum_b) = (0.0, 0.0, 0.0);
+        for (multiples, l, r) in LONGITUDE_DISTANCE.iter() {
+            let (angle, scale) = argument(multiples);
+            sum_l += *l as f64 * scale * angle.sin();
+            sum_r += *r as f64 * scale * angle.cos();
+        }
+        for (multiples, b) in LATITUDE.iter() {
+            let (angle, scale) = argument(multiples);
+            sum_b += *b as f64 * scale * angle.sin();
+        }
+
+        // additive terms for Venus, Jupiter and the flattening of the Earth
+        let sin = |deg: f64| deg.to_radians().sin();
+        sum_l +=
+            3_958.0 * sin(a1) + 1_962.0 * sin(mean_longitude - node_distance) + 318.0 * sin(a2);
+        sum_b += -2_235.0 * sin(mean_longitude)
+            + 382.0 * sin(a3)
+            + 175.0 * sin(a1 - node_distance)
+            + 175.0 * sin(a1 + node_distance)
+            + 127.0 * sin(mean_longitude - moon_anomaly)
+            - 115.0 * sin(mean_longitude + moon_anomaly);
+
+        LunarPosition {
+            longitude: (mean_longitude + sum_l / 1e6).rem_euclid(360.0),
+            latitude: sum_b / 1e6,
+            distance: 385_000.56 + sum_r / 1_000.0,
+        }
+    }
+
+    // equatorial horizontal parallax in degrees
+    pub fn parallax(&self, time: DateTime<Utc>) -> f64 {
+        (EARTH_RADIUS_KM / self.ecliptic_position(time).distance)
+            .asin()
+            .to_degrees()
+    }
+
+    // geocentric RA/Dec referred to the mean equator and equinox of date, i.e. without
+    // nutation; the frame `Accuracy::Standard` works in
+    pub fn mean_coords_of_date(&self, time: DateTime<Utc>) -> EquatorialCoords {
+        let position = self.ecliptic_position(time);
+        let ecliptic = EclipticCoords {
+            longitude: position.longitude,
+            latitude: position.latitude,
+        };
+        ecliptic_to_equatorial(ecliptic, mean_obliquity(time))
+    }
+
+    // geocentric apparent RA/Dec of date
+    pub fn coords_of_date(&self, time: DateTime<Utc>) -> EquatorialCoords {
+        let position = self.ecliptic_position(time);
+        let longitude = position.longitude + nutation(time).0;
+        let (sin_l, cos_l) = longitude.to_radians().sin_cos();
+        let (sin_b, cos_b) = position.latitude.to_radians().sin_cos();
+        let (sin_e, cos_e) = true_obliquity(time).to_radians().sin_cos();
+        let ra = (sin_l * cos_e - sin_b / cos_b * sin_e)
+            .atan2(cos_l)
+            .to_degrees()
+            .rem_euclid(360.0);
+        let dec = (sin_b * cos_e + cos_b * sin_e * sin_l).asin().to_degrees();
+        EquatorialCoords { ra, dec }
+    }
+
+    // RA/Dec as seen from the observer's position on the surface rather than the Earth's
+    // centre, which moves the Moon by up to a degree (Meeus ch. 40). Mean or apparent of date
+    // to match `options.accuracy`.
+    pub fn topocentric_coords(
+        &self,
+        observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> EquatorialCoords {
+        let geocentric = match options.accuracy {
+            Accuracy::Standard => self.mean_coords_of_date(time),
+            Accuracy::High => self.coords_of_date(time),
+        };
+        let lat = observer.location.lat().to_radians();
+        let u = (0.996_647_19 * lat.tan()).atan();
+        let height = observer.height / (EARTH_RADIUS_KM * 1_000.0);
+        let rho_sin = 0.996_647_19 * u.sin() + height * lat.sin();
+        let rho_cos = u.cos() + height * lat.cos();
+        let sin_parallax = self.parallax(time).to_radians().sin();
+
+        let lst = local_sidereal_time(
+            time,
+            observer.location.east_longitude(),
+            options.sidereal,
+            options.ut1_utc,
+        );
+        let (sin_ha, cos_ha) = (lst - geocentric.ra).to_radians().sin_cos();
+        let (sin_dec, cos_dec) = geocentric.dec.to_radians().sin_cos();
+        let delta_ra =
+            (-rho_cos * sin_parallax * sin_ha).atan2(cos_dec - rho_cos * sin_parallax * cos_ha);
+        let dec = ((sin_dec - rho_sin * sin_parallax) * delta_ra.cos())
+            .atan2(cos_dec - rho_cos * sin_parallax * cos_ha);
+
+        EquatorialCoords {
+            ra: (geocentric.ra + delta_ra.to_degrees()).rem_euclid(360.0),
+            dec: dec.to_degrees(),
+        }
+    }
+
+    // Sun-Moon-Earth angle in degrees, 0 at full moon and 180 at new moon (Meeus ch. 48)
+    pub fn phase_angle(&self, time: DateTime<Utc>) -> f64 {
+        // the Sun's apparent longitude includes nutation, so the Moon's has to as well
+        let moon = self.ecliptic_position(time);
+        let moon_longitude = moon.longitude + nutation(time).0;
+        let sun_longitude = Sun.apparent_longitude(time);
+        let sun_distance = solar_orbit(time).distance * AU_KM;
+        let elongation = (moon.latitude.to_radians().cos()
+            * (moon_longitude - sun_longitude).to_radians().cos())
+        .acos();
+        (sun_distance * elongation.sin())
+            .atan2(moon.distance - sun_distance * elongation.cos())
+            .to_degrees()
+    }
+
+    // fraction of the disk that is lit, 0 at new moon and 1 at full moon
+    pub fn illuminated_fraction(&self, time: DateTime<Utc>) -> f64 {
+        (1.0 + self.phase_angle(time).to_radians().cos()) / 2.0
+    }
+
+    // angular distance in degrees between the Moon and an object as seen by the observer.
+    // Both are taken as apparent places whatever `options.accuracy` says, so that aberration
+    // and nutation don't leave the two a few tens of arcseconds apart in different frames.
+    pub fn separation_from(
+        &self,
+        object: &AstroObject,
+        observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> f64 {
+        let apparent = CalculationOptions {
+            accuracy: Accuracy::High,
+            ..options
+        };
+        angular_separation(
+            self.topocentric_coords(observer, time, apparent),
+            object.coords_of_date_with(time, apparent),
+        )
+    }
+}
+
+impl Body for Moon {
+    fn name(&self) -> &str {
+        "Moon"
+    }
+
+    // always topocentric
+    fn coords_seen_from(
+        &self,
+        observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<EquatorialCoords> {
+        Some(self.topocentric_coords(observer, time, options))
+    }
+
+    // at high latitudes the Moon can stay below the horizon for most of a fortnight, and its
+    // declination runs through its whole range in a month
+    fn search_window(&self) -> Duration {
+        Duration::days(30)
+    }
+
+    // rise and set refer to the upper limb, whose size changes with the Moon's distance
+    fn limb_offset(&self, time: DateTime<Utc>) -> f64 {
+        MOON_RADIUS_RATIO * self.parallax(time)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rise_set::{RiseSet, STANDARD_HORIZON};
+    use crate::time_scales::{self, TimeScale};
+    use crate::GeoCoords;
+    use chrono::TimeZone;
+
+    fn longyearbyen<'a>() -> Observer<'a> {
+        Observer::new(GeoCoords::from_east_longitude(78.22, 15.63).unwrap())
+    }
+
+    // 1992 April 12 0h TD, the date of Meeus examples 47.a and 48.a
+    fn meeus_47a() -> DateTime<Utc> {
+        let reading = Utc.timestamp_opt(703_036_800, 0).unwrap();
+        time_scales::from_reading(reading, TimeScale::TT, None).unwrap()
+    }
+
+    #[test]
+    fn position_matches_meeus_47a() {
+        let position = Moon.ecliptic_position(meeus_47a());
+        assert!(
+            (position.longitude - 133.162_655).abs() < 1e-5,
+            "{:?}",
+            position
+        );
+        assert!(
+            (position.latitude + 3.229_126).abs() < 1e-5,
+            "{:?}",
+            position
+        );
+        assert!(
+            (position.distance - 368_409.7).abs() < 0.1,
+            "{:?}",
+            position
+        );
+    }
+
+    #[test]
+    fn phase_matches_meeus_48a() {
+        let phase_angle = Moon.phase_angle(meeus_47a());
+        assert!((phase_angle - 69.075_6).abs() < 5e-4, "{}", phase_angle);
+        let fraction = Moon.illuminated_fraction(meeus_47a());
+        assert!((fraction - 0.678_6).abs() < 1e-4, "{}", fraction);
+    }
+
+    #[test]
+    fn finds_events_days_away_at_high_latitude() {
+        // around the southern standstill of late January 2025 the Moon stays below the
+        // Svalbard horizon from the 21st to the 31st
+        let options = CalculationOptions::default();
+        let after = Utc.timestamp_opt(1_737_676_800, 0).unwrap();
+        assert!(
+            Moon.coords_as_alt_az_with(&longyearbyen(), after, options)
+                .unwrap()
+                .altitude
+                < 0.0
+        );
+        match Moon.next_rise(&longyearbyen(), after, STANDARD_HORIZON, options) {
+            RiseSet::Time(time) => {
+                assert!(time - after > Duration::days(4), "{}", time);
+                assert!(time - after < Duration::days(9), "{}", time);
+            }
+            event => panic!("expected a moonrise, got {:?}", event),
+        }
+
+        // and above it for days around the northern standstill on the 12th
+        let after = Utc.timestamp_opt(1_736_640_000, 0).unwrap();
+        match Moon.next_set(&longyearbyen(), after, STANDARD_HORIZON, options) {
+            RiseSet::Time(time) => assert!(time - after > Duration::days(2), "{}", time),
+            event => panic!("expected a moonset, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn separation_compares_apparent_places() {
+        let observer = longyearbyen();
+        let time = Utc.timestamp_opt(1_737_676_800, 0).unwrap();
+        let standard = CalculationOptions::default();
+        let high = CalculationOptions {
+            accuracy: Accuracy::High,
+            ..standard
+        };
+        // the result doesn't depend on the accuracy asked for, and matches the apparent places
+        let moon = Moon.topocentric_coords(&observer, time, high);
+        let object = AstroObject::new("Antares", 247.351_915, -26.432_003);
+        let target = object.coords_of_date_with(time, high);
+        let offset = angular_separation(moon, target);
+        assert!((Moon.separation_from(&object, &observer, time, standard) - offset).abs() < 1e-9);
+        assert_eq!(
+            Moon.separation_from(&object, &observer, time, standard),
+            Moon.separation_from(&object, &observer, time, high)
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::precession::mean_obliquity;
+use crate::ra_dec_calculations::calculate_days_since_j2000;
+use crate::units::ARCSEC_TO_DEG;
+use crate::vector::{self, Mat3};
+use chrono::{DateTime, Utc};
+
+const ARCSEC_PER_TURN: f64 = 1_296_000.0;
+// series amplitudes are in units of 0.1 microarcseconds
+const UNITS_TO_DEG: f64 = ARCSEC_TO_DEG / 1e7;
+// fixed offsets standing in for the planetary nutation terms, in arcseconds
+const PLANETARY_PSI: f64 = -0.000_135;
+const PLANETARY_EPS: f64 = 0.000_388;
+
+// IAU 2000B luni-solar series (McCarthy & Luzum 2003). Each row holds the multipliers of
+// l, l', F, D and Ω followed by the longitude sin, sin·t and cos coefficients and the
+// obliquity cos, cos·t and sin coefficients.
+#[rustfmt::skip]
+const SERIES: [([i8; 5], [f64; 6]); 77] = [
+    ([0, 0, 0, 0, 1], [-172064161.0, -174666.0, 33386.0, 92052331.0, 9086.0, 15377.0]),
+    ([0, 0, 2, -2, 2], [-13170906.0, -1675.0, -13696.0, 5730336.0, -3015.0, -4587.0]),
+    ([0, 0, 2, 0, 2], [-2276413.0, -234.0, 2796.0, 978459.0, -485.0, 1374.0]),
+    ([0, 0, 0, 0, 2], [2074554.0, 207.0, -698.0, -897492.0, 470.0, -291.0]),
+    ([0, 1, 0, 0, 0], [1475877.0, -3633.0, 11817.0, 73871.0, -184.0, -1924.0]),
+    ([0, 1, 2, -2, 2], [-516821.0, 1226.0, -524.0, 224386.0, -677.0, -174.0]),
+    ([1, 0, 0, 0, 0], [711159.0, 73.0, -872.0, -6750.0, 0.0, 358.0]),
+    ([0, 0, 2, 0, 1], [-387298.0, -367.0, 380.0, 200728.0, 18.0, 318.0]),
+    ([1, 0, 2, 0, 2], [-301461.0, -36.0, 816.0, 129025.0, -63.0, 367.0]),
+    ([0, -1, 2, -2, 2], [215829.0, -494.0, 111.0, -95929.0, 299.0, 132.0]),
+    ([0, 0, 2, -2, 1], [128227.0, 137.0, 181.0, -68982.0, -9.0, 39.0]),
+    ([-1, 0, 2, 0, 2], [123457.0, 11.0, 19.0, -53311.0, 32.0, -4.0]),
+    ([-1, 0, 0, 2, 0], [156994.0, 10.0, -168.0, -1235.0, 0.0, 82.0]),
+    ([1, 0, 0, 0, 1], [63110.0, 63.0, 27.0, -33228.0, 0.0, -9.0]),
+    ([-1, 0, 0, 0, 1], [-57976.0, -63.0, -189.0, 31429.0, 0.0, -75.0]),
+    ([-1, 0, 2, 2, 2], [-59641.0, -11.0, 149.0, 25543.0, -11.0, 66.0]),
+    ([1, 0, 2, 0, 1], [-51613.0, -42.0, 129.0, 26366.0, 0.0, 78.0]),
+    ([-2, 0, 2, 0, 1], [45893.0, 50.0, 31.0, -24236.0, -10.0, 20.0]),
+    ([0, 0, 0, 2, 0], [63384.0, 11.0, -150.0, -1220.0, 0.0, 29.0]),
+    ([0, 0, 2, 2, 2], [-38571.0, -1.0, 158.0, 16452.0, -11.0, 68.0]),
+    ([0, -2, 2, -2, 2], [32481.0, 0.0, 0.0, -13870.0, 0.0, 0.0]),
+    ([-2, 0, 0, 2, 0], [-47722.0, 0.0, -18.0, 477.0, 0.0, -25.0]),
+    ([2, 0, 2, 0, 2], [-31046.0, -1.0, 131.0, 13238.0, -11.0, 59.0]),
+    ([1, 0, 2, -2, 2], [28593.0, 0.0, -1.0, -12338.0, 10.0, -3.0]),
+    ([-1, 0, 2, 0, 1], [20441.0, 21.0, 10.0, -10758.0, 0.0, -3.0]),
+    ([2, 0, 0, 0, 0], [29243.0, 0.0, -74.0, -609.0, 0.0, 13.0]),
+    ([0, 0, 2, 0, 0], [25887.0, 0.0, -66.0, -550.0, 0.0, 11.0]),
+    ([0, 1, 0, 0, 1], [-14053.0, -25.0, 79.0, 8551.0, -2.0, -45.0]),
+    ([-1, 0, 0, 2, 1], [15164.0, 10.0, 11.0, -8001.0, 0.0, -1.0]),
+    ([0, 2, 2, -2, 2], [-15794.0, 72.0, -16.0, 6850.0, -42.0, -5.0]),
+    ([0, 0, -2, 2, 0], [21783.0, 0.0, 13.0, -167.0, 0.0, 13.0]),
+    ([1, 0, 0, -2, 1], [-12873.0, -10.0, -37.0, 6953.0, 0.0, -14.0]),
+    ([0, -1, 0, 0, 1], [-12654.0, 11.0, 63.0, 6415.0, 0.0, 26.0]),
+    ([-1, 0, 2, 2, 1], [-10204.0, 0.0, 25.0, 5222.0, 0.0, 15.0]),
+    ([0, 2, 0, 0, 0], [16707.0, -85.0, -10.0, 168.0, -1.0, 10.0]),
+    ([1, 0, 2, 2, 2], [-7691.0, 0.0, 44.0, 3268.0, 0.0, 19.0]),
+    ([-2, 0, 2, 0, 0], [-11024.0, 0.0, -14.0, 104.0, 0.0, 2.0]),
+    ([0, 1, 2, 0, 2], [7566.0, -21.0, -11.0, -3250.0, 0.0, -5.0]),
+    ([0, 0, 2, 2, 1], [-6637.0, -11.0, 25.0, 3353.0, 0.0, 14.0]),
+    ([0, -1, 2, 0, 2], [-7141.0, 21.0, 8.0, 3070.0, 0.0, 4.0]),
+    ([0, 0, 0, 2, 1], [-6302.0, -11.0, 2.0, 3272.0, 0.0, 4.0]),
+    ([1, 0, 2, -2, 1], [5800.0, 10.0, 2.0, -3045.0, 0.0, -1.0]),
+    ([2, 0, 2, -2, 2], [6443.0, 0.0, -7.0, -2768.0, 0.0, -4.0]),
+    ([-2, 0, 0, 2, 1], [-5774.0, -11.0, -15.0, 3041.0, 0.0, -5.0]),
+    ([2, 0, 2, 0, 1], [-5350.0, 0.0, 21.0, 2695.0, 0.0, 12.0]),
+    ([0, -1, 2, -2, 1], [-4752.0, -11.0, -3.0, 2719.0, 0.0, -3.0]),
+    ([0, 0, 0, -2, 1], [-4940.0, -11.0, -21.0, 2720.0, 0.0, -9.0]),
+    ([-1, -1, 0, 2, 0], [7350.0, 0.0, -8.0, -51.0, 0.0, 4.0]),
+    ([2, 0, 0, -2, 1], [4065.0, 0.0, 6.0, -2206.0, 0.0, 1.0]),
+    ([1, 0, 0, 2, 0], [6579.0, 0.0, -24.0, -199.0, 0.0, 2.0]),
+    ([0, 1, 2, -2, 1], [3579.0, 0.0, 5.0, -1900.0, 0.0, 1.0]),
+    ([1, -1, 0, 0, 0], [4725.0, 0.0, -6.0, -41.0, 0.0, 3.0]),
+    ([-2, 0, 2, 0, 2], [-3075.0, 0.0, -2.0, 1313.0, 0.0, -1.0]),
+    ([3, 0, 2, 0, 2], [-2904.0, 0.0, 15.0, 1233.0, 0.0, 7.0]),
+    ([0, -1, 0, 2, 0], [4348.0, 0.0, -10.0, -81.0, 0.0, 2.0]),
+    ([1, -1, 2, 0, 2], [-2878.0, 0.0, 8.0, 1232.0, 0.0, 4.0]),
+    ([0, 0, 0, 1, 0], [-4230.0, 0.0, 5.0, -20.0, 0.0, -2.0]),
+    ([-1, -1, 2, 2, 2], [-2819.0, 0.0, 7.0, 1207.0, 0.0, 3.0]),
+    ([-1, 0, 2, 0, 0], [-4056.0, 0.0, 5.0, 40.0, 0.0, -2.0]),
+    ([0, -1, 2, 2, 2], [-2647.0, 0.0, 11.0, 1129.0, 0.0, 5.0]),
+    ([-2, 0, 0, 0, 1], [-2294.0, 0.0, -10.0, 1266.0, 0.0, -4.0]),
+    ([1, 1, 2, 0, 2], [2481.0, 0.0, -7.0, -1062.0, 0.0, -3.0]),
+    ([2, 0, 0, 0, 1], [2179.0, 0.0, -2.0, -1129.0, 0.0, -2.0]),
+    ([-1, 1, 0, 1, 0], [3276.0, 0.0, 1.0, -9.0, 0.0, 0.0]),
+    ([1, 1, 0, 0, 0], [-3389.0, 0.0, 5.0, 35.0, 0.0, -2.0]),
+    ([1, 0, 2, 0, 0], [3339.0, 0.0, -13.0, -107.0, 0.0, 1.0]),
+    ([-1, 0, 2, -2, 1], [-1987.0, 0.0, -6.0, 1073.0, 0.0, -2.0]),
+    ([1, 0, 0, 0, 2], [-1981.0, 0.0, 0.0, 854.0, 0.0, 0.0]),
+    ([-1, 0, 0, 1, 0], [4026.0, 0.0, -353.0, -553.0, 0.0, -139.0]),
+    ([0, 0, 2, 1, 2], [1660.0, 0.0, -5.0, -710.0, 0.0, -2.0]),
+    ([-1, 0, 2, 4, 2], [-1521.0, 0.0, 9.0, 647.0, 0.0, 4.0]),
+    ([-1, 1, 0, 1, 1], [1314.0, 0.0, 0.0, -700.0, 0.0, 0.0]),
+    ([0, -2, 2, -2, 1], [-1283.0, 0.0, 0.0, 672.0, 0.0, 0.0]),
+    ([1, 0, 2, 2, 1], [-1331.0, 0.0, 8.0, 663.0, 0.0, 4.0]),
+    ([-2, 0, 2, 2, 2], [1383.0, 0.0, -2.0, -594.0, 0.0, -2.0]),
+    ([-1, 0, 0, 0, 2], [1405.0, 0.0, 4.0, -610.0, 0.0, 2.0]),
+    ([1, 1, 2, -2, 2], [1290.0, 0.0, 0.0, -556.0, 0.0, 0.0]),
+];
+
+fn fundamental_argument(at_epoch: f64, rate: f64, t: f64) -> f64 {
+    ((at_epoch + rate * t) % ARCSEC_PER_TURN) * ARCSEC_TO_DEG
+}
+
+// nutation in longitude and obliquity in degrees, IAU 2000B, good to about a milliarcsecond
+pub fn nutation(time: DateTime<Utc>) -> (f64, f64) {
+    let t = calculate_days_since_j2000(time) / 36_525.0;
+    let args = [
+        fundamental_argument(485_868.249_036, 1_717_915_923.217_8, t),
+        fundamental_argument(1_287_104.793_05, 129_596_581.048_1, t),
+        fundamental_argument(335_779.526_232, 1_739_527_262.847_8, t),
+        fundamental_argument(1_072_260.703_69, 1_602_961_601.209_0, t),
+        fundamental_argument(450_160.398_036, -6_962_890.543_1, t),
+    ];
+
+    let (mut d_psi, mut d_eps) = (0.0, 0.0);
+    // smallest terms first to limit rounding error
+    for (multipliers, coeffs) in SERIES.iter().rev() {
+        let arg: f64 = multipliers
+            .iter()
+            .zip(args.iter())
+            .map(|(&n, &a)| n as f64 * a)
+            .sum();
+        let (sin, cos) = arg.to_radians().sin_cos();
+        d_psi += (coeffs[0] + coeffs[1] * t) * sin + coeffs[2] * cos;
+        d_eps += (coeffs[3] + coeffs[4] * t) * cos + coeffs[5] * sin;
+    }
+
+    (
+        d_psi * UNITS_TO_DEG + PLANETARY_PSI * ARCSEC_TO_DEG,
+        d_eps * UNITS_TO_DEG + PLANETARY_EPS * ARCSEC_TO_DEG,
+    )
+}
+
+pub fn true_obliquity(time: DateTime<Utc>) -> f64 {
+    mean_obliquity(time) + nutation(time).1
+}
+
+// rotates mean equator and equinox of date to true equator and equinox of date
+pub fn nutation_matrix(time: DateTime<Utc>) -> Mat3 {
+    let (d_psi, d_eps) = nutation(time);
+    let eps = mean_obliquity(time);
+    vector::mul(
+        &vector::rot_x(-(eps + d_eps)),
+        &vector::mul(&vector::rot_z(-d_psi), &vector::rot_x(eps)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_scales::{self, TimeScale};
+    use chrono::TimeZone;
+
+    #[test]
+    fn matches_meeus_22a() {
+        // 1987 April 10 0h TD: Δψ −3.788″, Δε +9.443″ and ε 23°26′36.850″
+        let reading = Utc.timestamp_opt(545_011_200, 0).unwrap();
+        let time = time_scales::from_reading(reading, TimeScale::TT, None).unwrap();
+        // Meeus evaluates the IAU 1980 series, which sits up to about 0.01″ from 2000B
+        let (d_psi, d_eps) = nutation(time);
+        assert!((d_psi * 3600.0 + 3.788).abs() < 0.01, "{}", d_psi * 3600.0);
+        assert!((d_eps * 3600.0 - 9.443).abs() < 0.01, "{}", d_eps * 3600.0);
+        // and IAU 2006 starts the mean obliquity 0.042″ below the 1980 value
+        let eps = true_obliquity(time) - (23.0 + 26.0 / 60.0 + 36.850 / 3600.0);
+        assert!((eps * 3600.0 + 0.042).abs() < 0.01, "{}", eps * 3600.0);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::refraction::{Refraction, RefractionModel};
+use crate::GeoCoords;
+use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc};
+use std::fmt;
+
+// the UTC offset in force at a given instant. Every chrono `TimeZone` is one, so fixed offsets,
+// `Local` and the zones of chrono-tz all work and follow daylight saving time where they do
+pub trait LocalOffset: fmt::Debug {
+    fn offset_at(&self, time: DateTime<Utc>) -> FixedOffset;
+}
+
+impl<Tz: TimeZone + fmt::Debug> LocalOffset for Tz {
+    fn offset_at(&self, time: DateTime<Utc>) -> FixedOffset {
+        self.offset_from_utc_datetime(&time.naive_utc()).fix()
+    }
+}
+
+// a place on Earth plus what we need to know about the site for refraction, parallax and
+// local time. Height is in metres above the ellipsoid, pressure in hPa, temperature in °C
+// and relative humidity in [0, 1]. Humidity is kept for the caller's records but none of the
+// current refraction models use it. The timezone is borrowed so the observer stays `Copy`; it
+// sets local times and the local dates of `sun::sunrise` and friends, and defaults to UTC.
+#[derive(Clone, Copy, Debug)]
+pub struct Observer<'a> {
+    pub location: GeoCoords,
+    pub height: f64,
+    // None estimates the pressure from the height with a standard atmosphere
+    pub pressure: Option<f64>,
+    pub temperature: f64,
+    pub humidity: f64,
+    pub timezone: &'a dyn LocalOffset,
+    pub name: Option<&'a str>,
+}
+
+impl<'a> Observer<'a> {
+    pub fn new(location: GeoCoords) -> Observer<'a> {
+        Observer {
+            location,
+            height: 0.0,
+            pressure: None,
+            temperature: 10.0,
+            humidity: 0.5,
+            timezone: &Utc,
+            name: None,
+        }
+    }
+
+    pub fn with_height(mut self, height: f64) -> Observer<'a> {
+        self.height = height;
+        self
+    }
+
+    pub fn with_weather(mut self, pressure: f64, temperature: f64, humidity: f64) -> Observer<'a> {
+        self.pressure = Some(pressure);
+        self.temperature = temperature;
+        self.humidity = humidity;
+        self
+    }
+
+    pub fn with_timezone(mut self, timezone: &'a dyn LocalOffset) -> Observer<'a> {
+        self.timezone = timezone;
+        self
+    }
+
+    pub fn with_name(mut self, name: &'a str) -> Observer<'a> {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn pressure(&self) -> f64 {
+        // barometric formula with an 8.4 km scale height
+        self.pressure
+            .unwrap_or_else(|| 1013.25 * (-self.height / 8_434.5).exp())
+    }
+
+    pub fn refraction(&self, model: RefractionModel) -> Refraction {
+        Refraction {
+            model,
+            pressure: self.pressure(),
+            temperature: self.temperature,
+        }
+    }
+
+    pub fn local_time(&self, time: DateTime<Utc>) -> DateTime<FixedOffset> {
+        let offset = self.timezone.offset_at(time);
+        offset.from_utc_datetime(&time.naive_utc())
+    }
+}
+
+impl<'a> From<GeoCoords> for Observer<'a> {
+    fn from(location: GeoCoords) -> Observer<'a> {
+        Observer::new(location)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // a zone that changes from `before` to `after` hours east of UTC at one instant, standing in
+    // for a daylight saving rule
+    #[derive(Debug)]
+    pub(crate) struct Switching {
+        pub at: DateTime<Utc>,
+        pub before: i32,
+        pub after: i32,
+    }
+
+    impl LocalOffset for Switching {
+        fn offset_at(&self, time: DateTime<Utc>) -> FixedOffset {
+            let hours = if time < self.at {
+                self.before
+            } else {
+                self.after
+            };
+            FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    // 2025-03-09 10:00 UTC, when Los Angeles moved from PST to PDT
+    pub(crate) fn los_angeles_spring_2025() -> Switching {
+        Switching {
+            at: Utc.timestamp_opt(1_741_514_400, 0).unwrap(),
+            before: -8,
+            after: -7,
+        }
+    }
+
+    fn greenwich<'a>() -> Observer<'a> {
+        Observer::new(GeoCoords::from_east_longitude(51.48, 0.0).unwrap())
+    }
+
+    #[test]
+    fn pressure_falls_back_to_a_standard_atmosphere() {
+        assert_eq!(greenwich().pressure(), 1013.25);
+        let summit = greenwich().with_height(8_434.5);
+        assert!((summit.pressure() - 1013.25 / std::f64::consts::E).abs() < 1e-9);
+        let mauna_kea = greenwich().with_height(4_205.0);
+        assert!(
+            (mauna_kea.pressure() - 615.0).abs() < 5.0,
+            "{}",
+            mauna_kea.pressure()
+        );
+
+        // measured weather wins over the height
+        assert_eq!(summit.with_weather(990.0, 5.0, 0.3).pressure(), 990.0);
+    }
+
+    #[test]
+    fn local_time_follows_the_timezone() {
+        let before = Utc.timestamp_opt(1_741_514_399, 0).unwrap();
+        let after = Utc.timestamp_opt(1_741_514_400, 0).unwrap();
+
+        let utc = greenwich().local_time(before);
+        assert_eq!(utc.offset().local_minus_utc(), 0);
+        assert_eq!(
+            utc.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2025-03-09 09:59:59"
+        );
+
+        // any chrono time zone will do
+        let pst = FixedOffset::west_opt(8 * 3600).unwrap();
+        let fixed = greenwich().with_timezone(&pst).local_time(after);
+        assert_eq!(fixed.format("%H:%M").to_string(), "02:00");
+
+        // one second apart on either side of the clocks going forward
+        let zone = los_angeles_spring_2025();
+        let observer = greenwich().with_timezone(&zone);
+        assert_eq!(
+            observer.local_time(before).format("%H:%M:%S").to_string(),
+            "01:59:59"
+        );
+        assert_eq!(
+            observer.local_time(after).format("%H:%M:%S").to_string(),
+            "03:00:00"
+        );
+        assert_eq!(observer.local_time(after), after);
+    }
+}
@@ -0,0 +1,297 @@
+use crate::apparent::apparent_place;
+use crate::body::Body;
+use crate::observer::Observer;
+use crate::precession::{precess_to_date, Epoch, J2000_OBLIQUITY};
+use crate::ra_dec_calculations::{calculate_days_since_j2000, Accuracy, CalculationOptions};
+use crate::units::SECONDS_PER_DAY;
+use crate::vector::{self, Vec3};
+use crate::EquatorialCoords;
+use chrono::{DateTime, Duration, Utc};
+
+// light travel time for one AU, in days
+const LIGHT_DAYS_PER_AU: f64 = 0.005_775_518_3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+}
+
+// mean orbital elements at J2000 and their rates per century: semi-major axis (AU),
+// eccentricity, inclination, mean longitude, longitude of perihelion and longitude of the
+// ascending node (degrees), referred to the J2000 ecliptic and equinox. The last array holds
+// the extra mean anomaly terms b, c, s and f of the outer planets, zero for the others.
+type Elements = ([f64; 6], [f64; 6], [f64; 4]);
+
+// Standish, "Keplerian Elements for Approximate Positions of the Major Planets" (JPL),
+// tables 2a and 2b for 3000 BC to 3000 AD. Good to a few arcminutes inside that range, not a
+// VSOP87 replacement; outside it the elements are meaningless and no position is given.
+#[rustfmt::skip]
+const EARTH_MOON_BARYCENTER: Elements = (
+    [1.000_000_18, 0.016_731_63, -0.000_543_46, 100.466_915_72, 102.930_058_85, -5.112_603_89],
+    [-0.000_000_03, -0.000_036_61, -0.013_371_78, 35_999.373_063_29, 0.317_952_60, -0.241_238_56],
+    [0.0; 4],
+);
+
+// validity of the long-range tables, in Julian centuries from J2000
+const FIRST_CENTURY: f64 = -50.0;
+const LAST_CENTURY: f64 = 10.0;
+
+impl Planet {
+    pub const ALL: [Planet; 7] = [
+        Planet::Mercury,
+        Planet::Venus,
+        Planet::Mars,
+        Planet::Jupiter,
+        Planet::Saturn,
+        Planet::Uranus,
+        Planet::Neptune,
+    ];
+
+    #[rustfmt::skip]
+    fn elements(&self) -> Elements {
+        match self {
+            Planet::Mercury => (
+                [0.387_098_43, 0.205_636_61, 7.005_594_32, 252.251_667_24, 77.457_718_95, 48.339_618_19],
+                [0.0, 0.000_021_23, -0.005_901_58, 149_472.674_866_23, 0.159_400_13, -0.122_141_82],
+                [0.0; 4],
+            ),
+            Planet::Venus => (
+                [0.723_321_02, 0.006_763_99, 3.397_775_45, 181.979_708_50, 131.767_557_13, 76.672_614_96],
+                [-0.000_000_26, -0.000_051_07, 0.000_434_94, 58_517.815_602_60, 0.056_796_48, -0.272_741_74],
+                [0.0; 4],
+            ),
+            Planet::Mars => (
+                [1.523_712_43, 0.093_365_11, 1.851_818_69, -4.568_131_64, -23.917_447_84, 49.713_209_84],
+                [0.000_000_97, 0.000_091_49, -0.007_247_57, 19_140.299_342_43, 0.452_236_25, -0.268_524_31],
+                [0.0; 4],
+            ),
+            Planet::Jupiter => (
+                [5.202_480_19, 0.048_535_90, 1.298_614_16, 34.334_791_52, 14.274_952_44, 100.292_826_54],
+                [-0.000_028_64, 0.000_180_26, -0.003_226_99, 3_034.903_717_57, 0.181_991_96, 0.130_246_19],
+                [-0.000_124_52, 0.060_640_60, -0.356_354_38, 38.351_25],
+            ),
+            Planet::Saturn => (
+                [9.541_498_83, 0.055_508_25, 2.494_241_02, 50.075_713_29, 92.861_360_63, 113.639_987_02],
+                [-0.000_030_65, -0.000_320_44, 0.004_519_69, 1_222.114_947_24, 0.541_794_78, -0.250_150_02],
+                [0.000_258_99, -0.134_344_69, 0.873_201_47, 38.351_25],
+            ),
+            Planet::Uranus => (
+                [19.187_979_48, 0.046_857_40, 0.772_981_27, 314.202_766_25, 172.434_044_41, 73.962_502_15],
+                [-0.000_204_55, -0.000_015_50, -0.001_801_55, 428.495_125_95, 0.092_669_85, 0.057_396_99],
+                [0.000_583_31, -0.977_318_48, 0.176_892_45, 7.670_25],
+            ),
+            Planet::Neptune => (
+                [30.069_527_52, 0.008_954_39, 1.770_055_20, 304.222_892_87, 46.681_587_24, 131.786_358_53],
+                [0.000_064_47, 0.000_008_18, 0.000_224_00, 218.465_153_14, 0.010_099_38, -0.006_063_02],
+                [-0.000_413_48, 0.683_463_18, -0.101_625_47, 7.670_25],
+            ),
+        }
+    }
+
+    // whether `time` lies in the 3000 BC to 3000 AD range the elements were fitted to
+    pub fn covers(time: DateTime<Utc>) -> bool {
+        let t = calculate_days_since_j2000(time) / 36_525.0;
+        (FIRST_CENTURY..=LAST_CENTURY).contains(&t)
+    }
+
+    // heliocentric ecliptic position in AU, None outside the range of the elements
+    pub fn heliocentric_position(&self, time: DateTime<Utc>) -> Option<Vec3> {
+        if Planet::covers(time) {
+            Some(orbit_position(self.elements(), time))
+        } else {
+            None
+        }
+    }
+
+    // geometric distance from the Earth in AU
+    pub fn distance(&self, time: DateTime<Utc>) -> Option<f64> {
+        let earth = orbit_position(EARTH_MOON_BARYCENTER, time);
+        let planet = self.heliocentric_position(time)?;
+        let geocentric = vector::add_scaled(planet, earth, -1.0);
+        Some(vector::dot(geocentric, geocentric).sqrt())
+    }
+
+    // geocentric astrometric RA/Dec in the J2000 frame, i.e. where the planet was when the
+    // light now arriving left it
+    pub fn astrometric_coords(&self, time: DateTime<Utc>) -> Option<EquatorialCoords> {
+        if !Planet::covers(time) {
+            return None;
+        }
+        let earth = orbit_position(EARTH_MOON_BARYCENTER, time);
+        let mut light_time = 0.0;
+        let mut geocentric = [0.0; 3];
+        // two or three passes settle the light time to well under a second
+        for _ in 0..3 {
+            let emitted =
+                time - Duration::milliseconds((light_time * SECONDS_PER_DAY * 1000.0) as i64);
+            let planet = orbit_position(self.elements(), emitted);
+            geocentric = vector::add_scaled(planet, earth, -1.0);
+            light_time = vector::dot(geocentric, geocentric).sqrt() * LIGHT_DAYS_PER_AU;
+        }
+
+        let equatorial = vector::mul_vec(&vector::rot_x(-J2000_OBLIQUITY), geocentric);
+        let (ra, dec) = vector::to_spherical(equatorial);
+        Some(EquatorialCoords { ra, dec })
+    }
+}
+
+// heliocentric position in AU on the J2000 ecliptic from a set of mean elements
+fn orbit_position((elements, rates, extra): Elements, time: DateTime<Utc>) -> Vec3 {
+    let t = calculate_days_since_j2000(time) / 36_525.0;
+    let at = |i: usize| elements[i] + rates[i] * t;
+    let (a, e, inclination) = (at(0), at(1), at(2));
+    let (mean_longitude, perihelion, node) = (at(3), at(4), at(5));
+
+    let [b, c, s, f] = extra;
+    let (sin_ft, cos_ft) = (f * t).to_radians().sin_cos();
+    let perihelion_argument = (perihelion - node).to_radians();
+    let mean_anomaly = mean_longitude - perihelion + b * t * t + c * cos_ft + s * sin_ft;
+    let mean_anomaly = ((mean_anomaly + 180.0).rem_euclid(360.0) - 180.0).to_radians();
+
+    // Kepler's equation by Newton iteration
+    let mut eccentric_anomaly = mean_anomaly + e * mean_anomaly.sin();
+    for _ in 0..10 {
+        let delta = (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+            / (1.0 - e * eccentric_anomaly.cos());
+        eccentric_anomaly -= delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    let x = a * (eccentric_anomaly.cos() - e);
+    let y = a * (1.0 - e * e).sqrt() * eccentric_anomaly.sin();
+    let (sin_w, cos_w) = perihelion_argument.sin_cos();
+    let (sin_n, cos_n) = node.to_radians().sin_cos();
+    let (sin_i, cos_i) = inclination.to_radians().sin_cos();
+
+    [
+        (cos_w * cos_n - sin_w * sin_n * cos_i) * x + (-sin_w * cos_n - cos_w * sin_n * cos_i) * y,
+        (cos_w * sin_n + sin_w * cos_n * cos_i) * x + (-sin_w * sin_n + cos_w * cos_n * cos_i) * y,
+        (sin_w * sin_i) * x + (cos_w * sin_i) * y,
+    ]
+}
+
+impl Body for Planet {
+    fn name(&self) -> &str {
+        match self {
+            Planet::Mercury => "Mercury",
+            Planet::Venus => "Venus",
+            Planet::Mars => "Mars",
+            Planet::Jupiter => "Jupiter",
+            Planet::Saturn => "Saturn",
+            Planet::Uranus => "Uranus",
+            Planet::Neptune => "Neptune",
+        }
+    }
+
+    // a planet can spend months below the horizon at high latitudes; the slow outer planets
+    // even longer, in which case a missing rise means none within the year
+    fn search_window(&self) -> Duration {
+        Duration::days(366)
+    }
+
+    fn coords_seen_from(
+        &self,
+        _observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<EquatorialCoords> {
+        let astrometric = self.astrometric_coords(time)?;
+        Some(match options.accuracy {
+            Accuracy::Standard => precess_to_date(astrometric, Epoch::J2000, time),
+            Accuracy::High => {
+                apparent_place(astrometric, Epoch::J2000, time, options.light_deflection)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rise_set::{RiseSet, STANDARD_HORIZON};
+    use crate::time_scales::TimeScale;
+    use chrono::TimeZone;
+
+    fn assert_near(coords: EquatorialCoords, ra_hours: f64, dec: f64, tolerance: f64) {
+        let ra_error = ((coords.ra - ra_hours * 15.0 + 180.0).rem_euclid(360.0) - 180.0).abs();
+        assert!(ra_error < tolerance, "{:?}", coords);
+        assert!((coords.dec - dec).abs() < tolerance, "{:?}", coords);
+    }
+
+    #[test]
+    fn oppositions_match_published_positions() {
+        // Mars 2025-01-16, Saturn 2025-09-21, Jupiter 2026-01-10, all at 00:00 UTC; almanac
+        // positions are of date, about 0.3° of precession away from J2000
+        let mars = Planet::Mars.astrometric_coords(Utc.timestamp_opt(1_736_985_600, 0).unwrap());
+        assert_near(mars.unwrap(), 7.92, 25.2, 0.5);
+        let saturn =
+            Planet::Saturn.astrometric_coords(Utc.timestamp_opt(1_758_412_800, 0).unwrap());
+        assert_near(saturn.unwrap(), 23.95, -3.0, 0.5);
+        let jupiter =
+            Planet::Jupiter.astrometric_coords(Utc.timestamp_opt(1_768_003_200, 0).unwrap());
+        assert_near(jupiter.unwrap(), 7.43, 22.2, 0.5);
+    }
+
+    #[test]
+    fn venus_matches_meeus_33a() {
+        // 1992 December 20 0h TD: α 21h04m41.454s, δ −18°53′16.84″, Δ 0.910845 AU from the full
+        // VSOP87; the mean elements land within about 12″
+        let reading = Utc.timestamp_opt(724_809_600, 0).unwrap();
+        let time = crate::time_scales::from_reading(reading, TimeScale::TT, None).unwrap();
+        let observer = Observer::new(crate::GeoCoords::from_east_longitude(0.0, 0.0).unwrap());
+        let options = CalculationOptions {
+            accuracy: Accuracy::High,
+            ..CalculationOptions::default()
+        };
+        let coords = Planet::Venus
+            .coords_seen_from(&observer, time, options)
+            .unwrap();
+        let ra_error = (coords.ra - 316.172_725) * 3600.0 * coords.dec.to_radians().cos();
+        let dec_error = (coords.dec + 18.888_011) * 3600.0;
+        assert!(
+            ra_error.abs() < 20.0 && dec_error.abs() < 20.0,
+            "{:?}",
+            coords
+        );
+        assert!((Planet::Venus.distance(time).unwrap() - 0.910_845).abs() < 1e-4);
+    }
+
+    #[test]
+    fn refuses_dates_outside_the_tables() {
+        // 3500 AD and 3500 BC
+        let late = Utc.timestamp_opt(48_338_000_000, 0).unwrap();
+        let early = Utc.timestamp_opt(-172_600_000_000, 0).unwrap();
+        let inside = Utc.timestamp_opt(946_728_000, 0).unwrap();
+        for &planet in Planet::ALL.iter() {
+            assert!(planet.astrometric_coords(late).is_none());
+            assert!(planet.astrometric_coords(early).is_none());
+            assert!(planet.distance(late).is_none());
+            assert!(planet.astrometric_coords(inside).is_some());
+        }
+
+        let observer = Observer::new(crate::GeoCoords::from_west_longitude(34.05, 118.24).unwrap());
+        let options = CalculationOptions::default();
+        assert_eq!(
+            Planet::Mars.coords_seen_from(&observer, late, options),
+            None
+        );
+        assert_eq!(Planet::Mars.coords_as_alt_az_at(&observer, late), None);
+
+        // 3100 AD: no rise can be found, and the search says why instead of "never rises"
+        let year_3100 = Utc.timestamp_opt(35_659_355_760, 0).unwrap();
+        let rise = Planet::Mars.next_rise(&observer, year_3100, STANDARD_HORIZON, options);
+        assert_eq!(rise, RiseSet::NoEphemeris);
+        assert_eq!(
+            Planet::Mars.next_transit(&observer, year_3100, options),
+            None
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use crate::ra_dec_calculations::calculate_days_since_j2000;
+use crate::time_scales::{self, TimeScale};
+use crate::units::ARCSEC_TO_DEG;
+use crate::vector::{self, Mat3};
+use crate::EquatorialCoords;
+use chrono::{DateTime, TimeZone, Utc};
+
+// B1950.0 is JD 2433282.4235, expressed in Julian centuries from J2000
+const B1950_CENTURIES: f64 = (2_433_282.423_5 - 2_451_545.0) / 36_525.0;
+
+// the mean equator and equinox a set of catalog coordinates refers to
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Epoch {
+    #[default]
+    J2000,
+    B1950,
+    // already referred to the mean equator and equinox of the observation date
+    OfDate,
+}
+
+// both epochs are defined on TT (or its predecessor ET)
+fn from_tt(reading: DateTime<Utc>) -> DateTime<Utc> {
+    time_scales::from_reading(reading, TimeScale::TT, None)
+        .expect("J2000 and B1950 are well inside chrono's range")
+}
+
+impl Epoch {
+    // the instant the epoch names, which is also when catalog positions referred to it are
+    // usually valid. `OfDate` has no fixed instant.
+    pub fn instant(&self) -> Option<DateTime<Utc>> {
+        match self {
+            // 2000-01-01 12:00
+            Epoch::J2000 => Some(from_tt(Utc.timestamp_opt(946_728_000, 0).unwrap())),
+            // JD 2433282.4235, 1949-12-31 22:09:50.4
+            Epoch::B1950 => Some(from_tt(
+                Utc.timestamp_opt(-631_158_610, 400_000_000).unwrap(),
+            )),
+            Epoch::OfDate => None,
+        }
+    }
+}
+
+// obliquity of the J2000 ecliptic in degrees, IAU 2006 (84381.406″)
+pub const J2000_OBLIQUITY: f64 = 84_381.406 * ARCSEC_TO_DEG;
+
+// mean obliquity of the ecliptic in degrees, IAU 2006
+pub fn mean_obliquity(time: DateTime<Utc>) -> f64 {
+    let t = calculate_days_since_j2000(time) / 36_525.0;
+    let drift = t
+        * (-46.836_769
+            + t * (-0.000_183_1
+                + t * (0.002_003_40 + t * (-0.000_000_576 + t * -0.000_000_043_4))));
+    J2000_OBLIQUITY + drift * ARCSEC_TO_DEG
+}
+
+// IAU 2006 (P03) precession matrix from J2000 to the mean equator and equinox of date,
+// t in Julian centuries from J2000
+pub fn precession_matrix(t: f64) -> Mat3 {
+    let zeta = (2.650_545
+        + t * (2_306.083_227
+            + t * (0.298_849_9
+                + t * (0.018_018_28 + t * (-0.000_005_971 + t * -0.000_000_317_3)))))
+        * ARCSEC_TO_DEG;
+    let z = (-2.650_545
+        + t * (2_306.077_181
+            + t * (1.092_734_8
+                + t * (0.018_268_37 + t * (-0.000_028_596 + t * -0.000_000_290_4)))))
+        * ARCSEC_TO_DEG;
+    let theta = (t
+        * (2_004.191_903
+            + t * (-0.429_493_4
+                + t * (-0.041_822_64 + t * (-0.000_007_089 + t * -0.000_000_127_4)))))
+        * ARCSEC_TO_DEG;
+
+    vector::mul(
+        &vector::rot_z(-z),
+        &vector::mul(&vector::rot_y(theta), &vector::rot_z(-zeta)),
+    )
+}
+
+fn rotate(coords: EquatorialCoords, matrix: &Mat3) -> EquatorialCoords {
+    let v = vector::mul_vec(matrix, vector::from_spherical(coords.ra, coords.dec));
+    let (ra, dec) = vector::to_spherical(v);
+    EquatorialCoords { ra, dec }
+}
+
+pub fn to_j2000(coords: EquatorialCoords, epoch: Epoch, time: DateTime<Utc>) -> EquatorialCoords {
+    match epoch {
+        Epoch::J2000 => coords,
+        // this is a pure precession; the FK4 E-terms and equinox offset (under an arcsecond)
+        // are not removed
+        Epoch::B1950 => rotate(
+            coords,
+            &vector::transpose(&precession_matrix(B1950_CENTURIES)),
+        ),
+        Epoch::OfDate => {
+            let t = calculate_days_since_j2000(time) / 36_525.0;
+            rotate(coords, &vector::transpose(&precession_matrix(t)))
+        }
+    }
+}
+
+// catalog coordinates to the mean equator and equinox of the given instant
+pub fn precess_to_date(
+    coords: EquatorialCoords,
+    epoch: Epoch,
+    time: DateTime<Utc>,
+) -> EquatorialCoords {
+    if epoch == Epoch::OfDate {
+        return coords;
+    }
+    let t = calculate_days_since_j2000(time) / 36_525.0;
+    rotate(to_j2000(coords, epoch, time), &precession_matrix(t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ra_dec_calculations::angular_separation;
+
+    fn tt(timestamp: i64) -> DateTime<Utc> {
+        from_tt(Utc.timestamp_opt(timestamp, 0).unwrap())
+    }
+
+    #[test]
+    fn theta_persei_matches_meeus_21b() {
+        // J2000 place moved by its proper motion (+0.034 25 s and −0.0895″ a year) to
+        // 2028 November 13.19 TD, 28.867 Julian years on
+        let years = 28.867_0;
+        let j2000 = EquatorialCoords {
+            ra: 41.049_942 + 0.034_25 * 15.0 / 3600.0 * years,
+            dec: 49.228_467 - 0.089_5 / 3600.0 * years,
+        };
+        let of_date = precess_to_date(j2000, Epoch::J2000, tt(1_857_702_816));
+        // Meeus uses the IAU 1976 angles, which differ from IAU 2006 by about 0.1″ here
+        assert!((of_date.ra - 41.547_214).abs() < 5e-5, "{:?}", of_date);
+        assert!((of_date.dec - 49.348_483).abs() < 2e-5, "{:?}", of_date);
+    }
+
+    #[test]
+    fn j2000_to_date_and_back() {
+        let time = tt(1_857_702_816);
+        for ra_step in 0..12 {
+            for dec_step in -4..=4 {
+                let coords = EquatorialCoords {
+                    ra: ra_step as f64 * 30.0,
+                    dec: dec_step as f64 * 22.0,
+                };
+                let of_date = precess_to_date(coords, Epoch::J2000, time);
+                let back = to_j2000(of_date, Epoch::OfDate, time);
+                assert!(angular_separation(coords, back) < 1e-12, "{:?}", back);
+            }
+        }
+        let coords = EquatorialCoords { ra: 10.0, dec: 5.0 };
+        assert_eq!(precess_to_date(coords, Epoch::OfDate, time), coords);
+    }
+
+    #[test]
+    fn b1950_is_precession_from_its_own_instant() {
+        let b1950 = Epoch::B1950.instant().unwrap();
+        let coords = EquatorialCoords { ra: 0.0, dec: 0.0 };
+        let via_epoch = to_j2000(coords, Epoch::B1950, b1950);
+        let via_date = to_j2000(coords, Epoch::OfDate, b1950);
+        assert!(angular_separation(via_epoch, via_date) < 1e-9);
+        // half a century of general precession moves the equinox about 0.64° in RA
+        assert!((via_epoch.ra - 0.640).abs() < 0.005, "{:?}", via_epoch);
+        let back = precess_to_date(via_epoch, Epoch::J2000, b1950);
+        assert!(angular_separation(coords, back) < 1e-12);
+        assert!(Epoch::OfDate.instant().is_none());
+    }
+}
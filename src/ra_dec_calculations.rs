@@ -0,0 +1,467 @@
+use crate::apparent::catalog_place;
+use crate::iers::PolarMotion;
+use crate::observer::Observer;
+use crate::precession::{to_j2000, Epoch};
+use crate::refraction::RefractionModel;
+use crate::rise_set::STANDARD_HORIZON;
+use crate::sidereal::{local_sidereal_time, SiderealModel};
+use crate::time_scales::{self, TimeScale};
+use crate::vector;
+use crate::{EquatorialCoords, HorizontalCoords};
+use chrono::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Accuracy {
+    // mean place of date: precession only
+    #[default]
+    Standard,
+    // geocentric apparent place: precession, annual parallax, aberration and nutation
+    High,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CalculationOptions {
+    pub sidereal: SiderealModel,
+    pub accuracy: Accuracy,
+    // only used with `Accuracy::High`
+    pub light_deflection: bool,
+    // when set, altitudes are returned as observed through the observer's atmosphere
+    pub refraction: Option<RefractionModel>,
+    // UT1−UTC in seconds, e.g. from IERS Bulletin A; None assumes zero
+    pub ut1_utc: Option<f64>,
+    // pole offsets for the date, see `iers::EopTable::options_at`
+    pub polar_motion: Option<PolarMotion>,
+}
+
+impl CalculationOptions {
+    // apparent places need apparent sidereal time to give consistent hour angles
+    pub fn high_accuracy() -> CalculationOptions {
+        CalculationOptions {
+            sidereal: SiderealModel::Iau2006Apparent,
+            accuracy: Accuracy::High,
+            light_deflection: true,
+            refraction: None,
+            ut1_utc: None,
+            polar_motion: None,
+        }
+    }
+}
+
+// days from J2000.0 in TT, the time argument of the precession, nutation and ephemeris series
+pub fn calculate_days_since_j2000(time: DateTime<Utc>) -> f64 {
+    time_scales::days_since_j2000(time, TimeScale::TT, None)
+}
+
+// days from J2000.0 in UT1 for the Earth's rotation; `ut1_utc` in seconds, None to assume zero
+pub fn calculate_ut1_days_since_j2000(time: DateTime<Utc>, ut1_utc: Option<f64>) -> f64 {
+    time_scales::days_since_j2000(time, TimeScale::UT1, ut1_utc)
+}
+
+// `days_j2000` and `time` are both taken as UT1
+pub fn calculate_local_sidereal_time(days_j2000: f64, long: f64, time: DateTime<Utc>) -> f64 {
+    let seconds = time.second() as f64 + time.nanosecond() as f64 * 1e-9;
+    let fraction_of_hour = (time.minute() as f64 + seconds / 60.0) / 60.0;
+    let ut = time.hour() as f64 + fraction_of_hour;
+    // this is an approximate formula for local sidereal time taken from linked article. See readme.md
+    (100.46 + 0.985647 * days_j2000 + long + 15.0 * ut).rem_euclid(360.0)
+}
+
+pub fn calculate_alt_az(ha: f64, dec: f64, location: crate::GeoCoords) -> HorizontalCoords {
+    let (sin_ha, cos_ha) = ha.to_radians().sin_cos();
+    let (sin_dec, cos_dec) = dec.to_radians().sin_cos();
+    let (sin_lat, cos_lat) = location.lat().to_radians().sin_cos();
+
+    let prelim_alt = (sin_dec * sin_lat) + (cos_dec * cos_lat * cos_ha);
+    let alt = prelim_alt.clamp(-1.0, 1.0).asin().to_degrees();
+
+    // atan2 keeps the azimuth well defined near the zenith and at the poles, and puts objects
+    // east of the meridian (negative sin of the hour angle) between 0° and 180°
+    let az = (-cos_dec * sin_ha)
+        .atan2(sin_dec * cos_lat - cos_dec * cos_ha * sin_lat)
+        .to_degrees()
+        .rem_euclid(360.0);
+
+    HorizontalCoords {
+        altitude: alt,
+        azimuth: az,
+    }
+}
+
+// inverse of `calculate_alt_az`, returns (hour angle in [0, 360), declination) in degrees
+pub fn calculate_ha_dec(alt: f64, az: f64, location: crate::GeoCoords) -> (f64, f64) {
+    let (sin_alt, cos_alt) = alt.to_radians().sin_cos();
+    let (sin_az, cos_az) = az.to_radians().sin_cos();
+    let (sin_lat, cos_lat) = location.lat().to_radians().sin_cos();
+
+    let prelim_dec = sin_alt * sin_lat + cos_alt * cos_lat * cos_az;
+    let dec = prelim_dec.clamp(-1.0, 1.0).asin().to_degrees();
+
+    let ha = (-cos_alt * sin_az)
+        .atan2(sin_alt * cos_lat - cos_alt * cos_az * sin_lat)
+        .to_degrees()
+        .rem_euclid(360.0);
+
+    (ha, dec)
+}
+
+// angle between two directions in degrees, stable for both tiny and near 180° separations
+pub fn angular_separation(a: EquatorialCoords, b: EquatorialCoords) -> f64 {
+    let a = vector::from_spherical(a.ra, a.dec);
+    let b = vector::from_spherical(b.ra, b.dec);
+    let cross = vector::cross(a, b);
+    vector::dot(cross, cross)
+        .sqrt()
+        .atan2(vector::dot(a, b))
+        .to_degrees()
+}
+
+// the horizontal position together with the quantities guiding and field rotation code needs
+// for the same instant. Angles are in degrees with the hour angle in (-180, 180], positive
+// west of the meridian.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HorizontalDetails {
+    pub coords: HorizontalCoords,
+    pub hour_angle: f64,
+    pub parallactic_angle: f64,
+    // None below the apparent horizon
+    pub airmass: Option<f64>,
+}
+
+impl HorizontalDetails {
+    // hour angle in hours, within ±12h
+    pub fn hour_angle_hours(&self) -> f64 {
+        self.hour_angle / 15.0
+    }
+}
+
+// angle between the directions to the zenith and to the celestial pole at the object
+pub fn calculate_parallactic_angle(ha: f64, dec: f64, location: crate::GeoCoords) -> f64 {
+    let (sin_ha, cos_ha) = ha.to_radians().sin_cos();
+    let (sin_dec, cos_dec) = dec.to_radians().sin_cos();
+    let lat = location.lat().to_radians();
+    sin_ha
+        .atan2(lat.tan() * cos_dec - sin_dec * cos_ha)
+        .to_degrees()
+}
+
+// relative air mass from the geometric altitude, Young (1994), good to the horizon. None once
+// the object is below the apparent horizon, i.e. more than the standard 34′ of refraction
+// below the geometric one.
+pub fn calculate_airmass(alt: f64) -> Option<f64> {
+    if alt < STANDARD_HORIZON {
+        return None;
+    }
+    let c = (90.0 - alt).to_radians().cos();
+    Some(
+        (1.002_432 * c * c + 0.148_386 * c + 0.009_646_7)
+            / (c * c * c + 0.149_864 * c * c + 0.010_296_3 * c + 0.000_303_978),
+    )
+}
+
+// the observer's location referred to the instantaneous pole when polar motion is given
+fn effective_location(observer: &Observer, options: CalculationOptions) -> crate::GeoCoords {
+    match options.polar_motion {
+        Some(polar_motion) => polar_motion.apply(observer.location),
+        None => observer.location,
+    }
+}
+
+// coordinates of date (mean or apparent, matching `options.accuracy`) to the horizontal
+// frame of an observer
+pub fn equatorial_to_horizontal(
+    of_date: EquatorialCoords,
+    observer: &Observer,
+    time: DateTime<Utc>,
+    options: CalculationOptions,
+) -> HorizontalCoords {
+    equatorial_to_horizontal_details(of_date, observer, time, options).coords
+}
+
+pub fn equatorial_to_horizontal_details(
+    of_date: EquatorialCoords,
+    observer: &Observer,
+    time: DateTime<Utc>,
+    options: CalculationOptions,
+) -> HorizontalDetails {
+    let location = effective_location(observer, options);
+    let local_sidereal_time = local_sidereal_time(
+        time,
+        location.east_longitude(),
+        options.sidereal,
+        options.ut1_utc,
+    );
+    let mut hour_angle = local_sidereal_time - of_date.ra;
+    if hour_angle < 0.0 {
+        hour_angle += 360.0
+    };
+    let geometric = calculate_alt_az(hour_angle, of_date.dec, location);
+    let coords = match options.refraction {
+        Some(model) => observer.refraction(model).apply(geometric),
+        None => geometric,
+    };
+    if hour_angle > 180.0 {
+        hour_angle -= 360.0
+    };
+
+    HorizontalDetails {
+        coords,
+        hour_angle,
+        parallactic_angle: calculate_parallactic_angle(hour_angle, of_date.dec, location),
+        airmass: calculate_airmass(geometric.altitude),
+    }
+}
+
+// inverse of `equatorial_to_horizontal`: what an observer pointing at the given altitude and
+// azimuth is looking at, as coordinates of date. Refraction is taken out first when enabled.
+pub fn horizontal_to_equatorial(
+    horizontal: HorizontalCoords,
+    observer: &Observer,
+    time: DateTime<Utc>,
+    options: CalculationOptions,
+) -> EquatorialCoords {
+    let geometric = match options.refraction {
+        Some(model) => observer.refraction(model).remove(horizontal),
+        None => horizontal,
+    };
+    let location = effective_location(observer, options);
+    let (hour_angle, dec) = calculate_ha_dec(geometric.altitude, geometric.azimuth, location);
+    let local_sidereal_time = local_sidereal_time(
+        time,
+        location.east_longitude(),
+        options.sidereal,
+        options.ut1_utc,
+    );
+    EquatorialCoords {
+        ra: (local_sidereal_time - hour_angle).rem_euclid(360.0),
+        dec,
+    }
+}
+
+// like `horizontal_to_equatorial` but referred back to the J2000 catalog frame, undoing
+// precession (and nutation, aberration and deflection with `Accuracy::High`)
+pub fn horizontal_to_j2000(
+    horizontal: HorizontalCoords,
+    observer: &Observer,
+    time: DateTime<Utc>,
+    options: CalculationOptions,
+) -> EquatorialCoords {
+    let of_date = horizontal_to_equatorial(horizontal, observer, time, options);
+    match options.accuracy {
+        Accuracy::Standard => to_j2000(of_date, Epoch::OfDate, time),
+        Accuracy::High => catalog_place(of_date, time, options.light_deflection),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astro::astro_obj::AstroObject;
+    use crate::body::Body;
+    use crate::GeoCoords;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn los_angeles() -> GeoCoords {
+        GeoCoords::from_west_longitude(34.05, 118.24).unwrap()
+    }
+
+    #[test]
+    fn objects_east_of_the_meridian_have_eastern_azimuths() {
+        let east = calculate_alt_az(359.0, 20.0, los_angeles());
+        let west = calculate_alt_az(1.0, 20.0, los_angeles());
+        assert!(east.azimuth > 90.0 && east.azimuth < 180.0, "{:?}", east);
+        assert!(west.azimuth > 180.0 && west.azimuth < 270.0, "{:?}", west);
+        assert!((east.altitude - west.altitude).abs() < EPSILON);
+        assert!((east.azimuth + west.azimuth - 360.0).abs() < EPSILON);
+        assert!(east.altitude > 70.0 && east.altitude < 76.0);
+    }
+
+    #[test]
+    fn lower_culmination_is_symmetric_about_north() {
+        // circumpolar from Los Angeles, coming down in the north-west before the lower
+        // meridian and climbing in the north-east after it
+        let before = calculate_alt_az(179.0, 80.0, los_angeles());
+        let after = calculate_alt_az(181.0, 80.0, los_angeles());
+        assert!(
+            before.azimuth > 355.0 && before.azimuth < 360.0,
+            "{:?}",
+            before
+        );
+        assert!(after.azimuth > 0.0 && after.azimuth < 5.0, "{:?}", after);
+        assert!((before.altitude - after.altitude).abs() < EPSILON);
+        assert!((before.azimuth + after.azimuth - 360.0).abs() < EPSILON);
+        assert!(before.altitude > 0.0 && before.altitude < 30.0);
+    }
+
+    #[test]
+    fn transit_south_and_north_of_the_zenith() {
+        let south = calculate_alt_az(0.0, 10.0, los_angeles());
+        assert!((south.altitude - 65.95).abs() < EPSILON);
+        assert!((south.azimuth - 180.0).abs() < EPSILON);
+
+        let north = calculate_alt_az(0.0, 50.0, los_angeles());
+        assert!((north.altitude - 74.05).abs() < EPSILON);
+        assert!(north.azimuth.abs() < EPSILON);
+    }
+
+    #[test]
+    fn object_at_the_zenith() {
+        let zenith = calculate_alt_az(0.0, 34.05, los_angeles());
+        assert!((zenith.altitude - 90.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn altitude_equals_declination_at_the_poles() {
+        let north_pole = GeoCoords::try_new(90.0, 0.0).unwrap();
+        let south_pole = GeoCoords::try_new(-90.0, 0.0).unwrap();
+        for &ha in &[0.0, 45.0, 179.0, 181.0, 300.0] {
+            let north = calculate_alt_az(ha, 25.0, north_pole);
+            assert!((north.altitude - 25.0).abs() < EPSILON, "{:?}", north);
+            let south = calculate_alt_az(ha, 25.0, south_pole);
+            assert!((south.altitude + 25.0).abs() < EPSILON, "{:?}", south);
+            assert!((0.0..360.0).contains(&north.azimuth));
+        }
+    }
+
+    // every 15° of RA and 10° of Dec, to alt/az with `coords_as_alt_az_with` and back
+    fn assert_round_trip(options: CalculationOptions) {
+        let observer = Observer::new(los_angeles());
+        let time = Utc.timestamp_opt(1_780_000_000, 0).unwrap();
+        for ra_step in 0..24 {
+            for dec_step in -8..=8 {
+                let object =
+                    AstroObject::new("grid", ra_step as f64 * 15.0, dec_step as f64 * 10.0);
+                let horizontal = object
+                    .coords_as_alt_az_with(&observer, time, options)
+                    .unwrap();
+
+                let of_date = horizontal_to_equatorial(horizontal, &observer, time, options);
+                let expected = object.coords_of_date_with(time, options);
+                assert!(
+                    angular_separation(of_date, expected) < 1e-10,
+                    "{:?}",
+                    object
+                );
+
+                let j2000 = horizontal_to_j2000(horizontal, &observer, time, options);
+                let error = angular_separation(j2000, object.equatorial_coords());
+                assert!(error < 1e-12, "{:?} off by {}°", object, error);
+            }
+        }
+    }
+
+    #[test]
+    fn horizontal_round_trip_across_the_sky() {
+        assert_round_trip(CalculationOptions::default());
+    }
+
+    #[test]
+    fn horizontal_round_trip_across_the_sky_high_accuracy() {
+        assert_round_trip(CalculationOptions::high_accuracy());
+    }
+
+    #[test]
+    fn venus_from_washington_matches_meeus() {
+        // Meeus example 13.b: 1987 April 10 19:21:00 UT, apparent place of Venus
+        let washington = GeoCoords::from_west_longitude(38.921_389, 77.065_556).unwrap();
+        let observer = Observer::new(washington);
+        let time = Utc.timestamp_opt(545_080_860, 0).unwrap();
+        let venus = EquatorialCoords {
+            ra: 347.319_337_5,
+            dec: -6.719_891_7,
+        };
+        let options = CalculationOptions {
+            sidereal: SiderealModel::Iau2006Apparent,
+            ..CalculationOptions::default()
+        };
+        let horizontal = equatorial_to_horizontal(venus, &observer, time, options);
+        // Meeus gives A = 68.0337° from the south and h = 15.1249°
+        assert!(
+            (horizontal.altitude - 15.124_9).abs() < 1e-4,
+            "{:?}",
+            horizontal
+        );
+        assert!(
+            (horizontal.azimuth - 248.033_7).abs() < 1e-4,
+            "{:?}",
+            horizontal
+        );
+    }
+
+    #[test]
+    fn f64_day_counts_keep_sub_millisecond_resolution() {
+        // about 9,400 days after J2000, where an f32 day count steps by 2⁻¹⁰ days
+        let days = 9_400.000_5_f64;
+        let f32_error = (days - days as f32 as f64).abs();
+        assert!(f32_error * 86_400.0 > 40.0);
+        // the sidereal rotation multiplies that into more than ten arcminutes
+        assert!(f32_error * 360.985_647 * 60.0 > 10.0);
+
+        let time = Utc.timestamp_opt(1_758_945_643, 250_000_000).unwrap();
+        let later = time + chrono::Duration::milliseconds(1);
+        let step = calculate_days_since_j2000(later) - calculate_days_since_j2000(time);
+        assert!((step * 86_400.0 - 0.001).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hour_angle_is_normalised_and_given_in_hours_too() {
+        let observer = Observer::new(los_angeles());
+        let time = Utc.timestamp_opt(1_780_000_000, 0).unwrap();
+        let options = CalculationOptions::default();
+        let lst = local_sidereal_time(
+            time,
+            observer.location.east_longitude(),
+            options.sidereal,
+            options.ut1_utc,
+        );
+        for &expected in &[-179.5, -90.0, -0.25, 0.0, 45.0, 135.0, 179.5] {
+            let coords = EquatorialCoords {
+                ra: (lst - expected).rem_euclid(360.0),
+                dec: 10.0,
+            };
+            let details = equatorial_to_horizontal_details(coords, &observer, time, options);
+            assert!(details.hour_angle > -180.0 && details.hour_angle <= 180.0);
+            assert!(
+                (details.hour_angle - expected).abs() < 1e-9,
+                "{:?}",
+                details
+            );
+            assert!((details.hour_angle_hours() - expected / 15.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn parallactic_angle_is_negative_east_and_positive_west() {
+        let location = los_angeles();
+        // south of the zenith on the meridian the pole is straight "up" from the object
+        assert!(calculate_parallactic_angle(0.0, 10.0, location).abs() < 1e-12);
+        for &ha in &[15.0, 45.0, 90.0, 150.0] {
+            let west = calculate_parallactic_angle(ha, 10.0, location);
+            let east = calculate_parallactic_angle(-ha, 10.0, location);
+            assert!(west > 0.0 && east < 0.0, "{} {}", west, east);
+            assert!((west + east).abs() < 1e-12);
+        }
+        // on the celestial equator six hours west, tan q = 1 / tan φ
+        let q = calculate_parallactic_angle(90.0, 0.0, location);
+        assert!((q - (90.0 - 34.05)).abs() < 1e-9, "{}", q);
+    }
+
+    #[test]
+    fn airmass_from_zenith_to_horizon() {
+        assert!((calculate_airmass(90.0).unwrap() - 1.0).abs() < 1e-3);
+        // close to sec z = 2 at 30°
+        assert!((calculate_airmass(30.0).unwrap() - 1.995).abs() < 5e-3);
+        // about 32 where the geometric altitude is zero and the object still appears half a
+        // degree up, and about 38 on the apparent horizon
+        assert!((calculate_airmass(0.0).unwrap() - 31.7).abs() < 0.1);
+        assert!((calculate_airmass(STANDARD_HORIZON).unwrap() - 38.0).abs() < 0.5);
+        assert_eq!(calculate_airmass(-0.6), None);
+
+        let mut previous = 0.0;
+        for step in (0..=90).rev() {
+            let airmass = calculate_airmass(step as f64).unwrap();
+            assert!(airmass > previous, "{} at {}°", airmass, step);
+            previous = airmass;
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::HorizontalCoords;
+
+// refraction formulas misbehave a few degrees below the horizon and nothing down there is
+// seen through the atmosphere anyway. Below LOWEST_ALTITUDE the refraction fades out linearly
+// and is zero from NO_REFRACTION_BELOW down; a hard cut would leave a gap that the
+// inversions in `solve` can't cross.
+const LOWEST_ALTITUDE: f64 = -1.0;
+const NO_REFRACTION_BELOW: f64 = -2.0;
+
+fn faded_below_horizon(alt: f64, formula: impl Fn(f64) -> f64) -> f64 {
+    if alt >= LOWEST_ALTITUDE {
+        return formula(alt);
+    }
+    let fade = (alt - NO_REFRACTION_BELOW) / (LOWEST_ALTITUDE - NO_REFRACTION_BELOW);
+    formula(LOWEST_ALTITUDE) * fade.max(0.0)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RefractionModel {
+    // Bennett (1982), refraction as a function of apparent altitude, good to 0.07′
+    Bennett,
+    // Saemundsson (1986), refraction as a function of true altitude, consistent with Bennett to 0.1′
+    #[default]
+    Saemundsson,
+}
+
+// pressure in hPa (millibar), temperature in °C
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Refraction {
+    pub model: RefractionModel,
+    pub pressure: f64,
+    pub temperature: f64,
+}
+
+impl Default for Refraction {
+    // the standard conditions both formulas are calibrated for
+    fn default() -> Refraction {
+        Refraction {
+            model: RefractionModel::default(),
+            pressure: 1010.0,
+            temperature: 10.0,
+        }
+    }
+}
+
+// refraction in degrees from the apparent altitude in degrees
+fn bennett(apparent_alt: f64) -> f64 {
+    faded_below_horizon(apparent_alt, |h| {
+        1.0 / (h + 7.31 / (h + 4.4)).to_radians().tan() / 60.0
+    })
+}
+
+// refraction in degrees from the true altitude in degrees
+fn saemundsson(true_alt: f64) -> f64 {
+    faded_below_horizon(true_alt, |h| {
+        1.02 / (h + 10.3 / (h + 5.11)).to_radians().tan() / 60.0
+    })
+}
+
+// fixed point iteration for inverting a formula, converges in a handful of steps since the
+// refraction changes slowly compared to the altitude
+fn solve(start: f64, step: impl Fn(f64) -> f64) -> f64 {
+    let mut alt = start;
+    for _ in 0..50 {
+        let next = step(alt);
+        if (next - alt).abs() < 1e-10 {
+            return next;
+        }
+        alt = next;
+    }
+    alt
+}
+
+impl Refraction {
+    fn scale(&self) -> f64 {
+        (self.pressure / 1010.0) * (283.0 / (273.0 + self.temperature))
+    }
+
+    // how much higher an object at the given true altitude appears, in degrees
+    pub fn amount(&self, true_alt: f64) -> f64 {
+        self.apparent_altitude(true_alt) - true_alt
+    }
+
+    // true (geometric) altitude to the altitude an observer sees
+    pub fn apparent_altitude(&self, true_alt: f64) -> f64 {
+        match self.model {
+            RefractionModel::Saemundsson => true_alt + self.scale() * saemundsson(true_alt),
+            RefractionModel::Bennett => {
+                // Bennett is written in terms of the apparent altitude we are solving for
+                solve(true_alt, |apparent| {
+                    true_alt + self.scale() * bennett(apparent)
+                })
+            }
+        }
+    }
+
+    // observed altitude back to the true (geometric) altitude
+    pub fn true_altitude(&self, apparent_alt: f64) -> f64 {
+        match self.model {
+            RefractionModel::Bennett => apparent_alt - self.scale() * bennett(apparent_alt),
+            RefractionModel::Saemundsson => solve(apparent_alt, |true_alt| {
+                apparent_alt - self.scale() * saemundsson(true_alt)
+            }),
+        }
+    }
+
+    pub fn apply(&self, coords: HorizontalCoords) -> HorizontalCoords {
+        HorizontalCoords {
+            altitude: self.apparent_altitude(coords.altitude).min(90.0),
+            azimuth: coords.azimuth,
+        }
+    }
+
+    pub fn remove(&self, coords: HorizontalCoords) -> HorizontalCoords {
+        HorizontalCoords {
+            altitude: self.true_altitude(coords.altitude),
+            azimuth: coords.azimuth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_model(model: RefractionModel) -> Refraction {
+        Refraction {
+            model,
+            ..Refraction::default()
+        }
+    }
+
+    const MODELS: [RefractionModel; 2] = [RefractionModel::Bennett, RefractionModel::Saemundsson];
+
+    #[test]
+    fn horizon_and_mid_sky_values() {
+        for &model in &MODELS {
+            let refraction = with_model(model);
+            // about 34′ at the horizon and 1′ at 45°
+            let horizon = refraction.amount(0.0) * 60.0;
+            assert!((28.0..=36.0).contains(&horizon), "{:?} {}", model, horizon);
+            let mid_sky = refraction.amount(45.0) * 60.0;
+            assert!((mid_sky - 1.0).abs() < 0.05, "{:?} {}", model, mid_sky);
+            assert!(refraction.amount(90.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn no_refraction_well_below_the_horizon() {
+        for &model in &MODELS {
+            let refraction = with_model(model);
+            let below = HorizontalCoords {
+                altitude: -45.0,
+                azimuth: 10.0,
+            };
+            assert_eq!(refraction.apply(below), below);
+            assert_eq!(refraction.remove(below), below);
+            assert_eq!(refraction.amount(-2.5), 0.0);
+            // fading out between −1° and −2°
+            let fading = refraction.amount(-1.9);
+            assert!(fading > 0.0 && fading < 1.0, "{:?}", model);
+        }
+    }
+
+    #[test]
+    fn apparent_and_true_altitude_invert_each_other() {
+        for &model in &MODELS {
+            let refraction = with_model(model);
+            for step in 0..=190 {
+                let true_alt = -5.0 + step as f64 * 0.5;
+                let apparent = refraction.apparent_altitude(true_alt);
+                let back = refraction.true_altitude(apparent);
+                assert!((back - true_alt).abs() < 1e-8, "{:?} {}", model, true_alt);
+            }
+        }
+    }
+}
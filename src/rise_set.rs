@@ -0,0 +1,268 @@
+use chrono::{DateTime, Duration, Utc};
+
+// geometric altitude of a star's centre at rising and setting once the standard 34′ of
+// horizon refraction is accounted for
+pub const STANDARD_HORIZON: f64 = -0.5667;
+
+// events are searched for by sampling at this spacing and then bisecting, so an object that
+// pokes above the horizon for less than this can be missed
+const STEP_MINUTES: i64 = 10;
+// long enough to always contain the next rise and set of a fixed object and the next transit
+// of anything, but not the next rise of a body that can stay down for days at a time
+pub const FIXED_OBJECT_SEARCH_HOURS: i64 = 48;
+const BISECTIONS: u32 = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RiseSet {
+    Time(DateTime<Utc>),
+    // above the horizon for the whole search window
+    Circumpolar,
+    // below the horizon for the whole search window
+    NeverRises,
+    // the body has no position for part of the search window, e.g. a planet outside the
+    // range of its orbital elements, so nothing can be said
+    NoEphemeris,
+}
+
+// a sample during the search had no value
+struct NoEphemeris;
+
+// first time after `after` where `value_at` goes from below zero to at or above it
+// (`upward`), or the reverse, refined by bisection
+fn find_crossing(
+    value_at: &dyn Fn(DateTime<Utc>) -> Option<f64>,
+    after: DateTime<Utc>,
+    window: Duration,
+    upward: bool,
+) -> Result<Option<DateTime<Utc>>, NoEphemeris> {
+    let value_at = |time| value_at(time).ok_or(NoEphemeris);
+    let step = Duration::minutes(STEP_MINUTES);
+    let crossed = |before: f64, now: f64| {
+        if upward {
+            before < 0.0 && now >= 0.0
+        } else {
+            before >= 0.0 && now < 0.0
+        }
+    };
+
+    let mut start = after;
+    let mut start_value = value_at(start)?;
+    for _ in 0..(window.num_minutes() / STEP_MINUTES).max(1) {
+        let end = start + step;
+        let end_value = value_at(end)?;
+        if crossed(start_value, end_value) {
+            let (mut low, mut high) = (start, end);
+            let mut low_value = start_value;
+            for _ in 0..BISECTIONS {
+                let mid = low + (high - low) / 2;
+                let mid_value = value_at(mid)?;
+                if crossed(low_value, mid_value) {
+                    high = mid;
+                } else {
+                    low = mid;
+                    low_value = mid_value;
+                }
+            }
+            return Ok(Some(high));
+        }
+        start = end;
+        start_value = end_value;
+    }
+    Ok(None)
+}
+
+fn rise_or_set(
+    altitude_at: &dyn Fn(DateTime<Utc>) -> Option<f64>,
+    after: DateTime<Utc>,
+    horizon: f64,
+    window: Duration,
+    rising: bool,
+) -> RiseSet {
+    let above_horizon = |time| altitude_at(time).map(|altitude| altitude - horizon);
+    match find_crossing(&above_horizon, after, window, rising) {
+        Ok(Some(time)) => RiseSet::Time(time),
+        Ok(None) if above_horizon(after) >= Some(0.0) => RiseSet::Circumpolar,
+        Ok(None) => RiseSet::NeverRises,
+        Err(NoEphemeris) => RiseSet::NoEphemeris,
+    }
+}
+
+// `altitude_at` gives the altitude in degrees at an instant, or None where there is no
+// position; the horizon is in degrees. Nothing after `after + window` is looked at.
+pub fn next_rise(
+    altitude_at: impl Fn(DateTime<Utc>) -> Option<f64>,
+    after: DateTime<Utc>,
+    horizon: f64,
+    window: Duration,
+) -> RiseSet {
+    rise_or_set(&altitude_at, after, horizon, window, true)
+}
+
+pub fn next_set(
+    altitude_at: impl Fn(DateTime<Utc>) -> Option<f64>,
+    after: DateTime<Utc>,
+    horizon: f64,
+    window: Duration,
+) -> RiseSet {
+    rise_or_set(&altitude_at, after, horizon, window, false)
+}
+
+// upper transit is where the hour angle (degrees in (-180, 180]) passes through zero going
+// from east to west; the jump at lower transit goes the other way so it is never picked up.
+// Every body transits within the search window, so None means there was no position.
+pub fn next_transit(
+    hour_angle_at: impl Fn(DateTime<Utc>) -> Option<f64>,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let window = Duration::hours(FIXED_OBJECT_SEARCH_HOURS);
+    find_crossing(&hour_angle_at, after, window, true).unwrap_or(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astro::astro_obj::AstroObject;
+    use crate::body::Body;
+    use crate::observer::Observer;
+    use crate::ra_dec_calculations::CalculationOptions;
+    use crate::refraction::RefractionModel;
+    use crate::GeoCoords;
+    use chrono::TimeZone;
+
+    fn los_angeles<'a>() -> Observer<'a> {
+        Observer::new(GeoCoords::from_west_longitude(34.05, 118.24).unwrap())
+    }
+
+    fn start() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_780_000_000, 0).unwrap()
+    }
+
+    fn time_of(event: RiseSet) -> DateTime<Utc> {
+        match event {
+            RiseSet::Time(time) => time,
+            other => panic!("expected a time, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rises_transits_and_sets_a_fixed_star() {
+        let sirius = AstroObject::new("Sirius", 101.287_155, -16.716_116);
+        let observer = los_angeles();
+        let options = CalculationOptions::default();
+        let rise = time_of(sirius.next_rise(&observer, start(), STANDARD_HORIZON, options));
+        let transit = sirius.next_transit(&observer, rise, options).unwrap();
+        let set = time_of(sirius.next_set(&observer, rise, STANDARD_HORIZON, options));
+        assert!(rise < transit && transit < set);
+
+        // at the events the star sits on the horizon, and at transit on the meridian
+        for &time in &[rise, set] {
+            let altitude = sirius
+                .coords_as_alt_az_with(&observer, time, options)
+                .unwrap();
+            assert!(
+                (altitude.altitude - STANDARD_HORIZON).abs() < 1e-4,
+                "{:?}",
+                altitude
+            );
+        }
+        let details = sirius
+            .horizontal_details(&observer, transit, options)
+            .unwrap();
+        assert!(details.hour_angle.abs() < 1e-4, "{:?}", details);
+
+        // the semi-diurnal arc from cos H₀ = (sin h₀ − sin φ sin δ) / (cos φ cos δ), run at the
+        // sidereal rate of 15.041 07°/h, and transit halfway between
+        let (lat, dec) = (34.05f64.to_radians(), (-16.716_116f64).to_radians());
+        let cos_h0 =
+            (STANDARD_HORIZON.to_radians().sin() - lat.sin() * dec.sin()) / (lat.cos() * dec.cos());
+        let arc_hours = 2.0 * cos_h0.acos().to_degrees() / 15.041_07;
+        let measured = (set - rise).num_milliseconds() as f64 / 3_600_000.0;
+        assert!(
+            (measured - arc_hours).abs() < 0.01,
+            "{} {}",
+            measured,
+            arc_hours
+        );
+        let halfway = rise + (set - rise) / 2;
+        assert!((transit - halfway).num_seconds().abs() < 60);
+    }
+
+    #[test]
+    fn circumpolar_and_never_rising_stars_say_so() {
+        let observer = los_angeles();
+        let options = CalculationOptions::default();
+        let polaris = AstroObject::new("Polaris", 37.954_561, 89.264_109);
+        assert_eq!(
+            polaris.next_rise(&observer, start(), STANDARD_HORIZON, options),
+            RiseSet::Circumpolar
+        );
+        assert_eq!(
+            polaris.next_set(&observer, start(), STANDARD_HORIZON, options),
+            RiseSet::Circumpolar
+        );
+        assert!(polaris.next_transit(&observer, start(), options).is_some());
+
+        let sigma_octantis = AstroObject::new("σ Oct", 317.195_164, -88.956_499);
+        assert_eq!(
+            sigma_octantis.next_rise(&observer, start(), STANDARD_HORIZON, options),
+            RiseSet::NeverRises
+        );
+        assert_eq!(
+            sigma_octantis.next_set(&observer, start(), STANDARD_HORIZON, options),
+            RiseSet::NeverRises
+        );
+    }
+
+    #[test]
+    fn refraction_is_not_counted_twice() {
+        let sirius = AstroObject::new("Sirius", 101.287_155, -16.716_116);
+        let observer = los_angeles();
+        let plain = CalculationOptions::default();
+        let refracted = CalculationOptions {
+            refraction: Some(RefractionModel::Saemundsson),
+            ..plain
+        };
+        assert_eq!(
+            sirius.next_rise(&observer, start(), STANDARD_HORIZON, refracted),
+            sirius.next_rise(&observer, start(), STANDARD_HORIZON, plain)
+        );
+    }
+
+    #[test]
+    fn searches_stop_at_the_window_and_at_missing_values() {
+        // a sine with a 24 hour period crossing zero upwards at 06:00 and down at 18:00
+        let midnight = Utc.timestamp_opt(1_780_012_800, 0).unwrap();
+        let wave = |time: DateTime<Utc>| {
+            let hours = (time - midnight).num_seconds() as f64 / 3600.0;
+            Some(((hours - 6.0) * 15.0).to_radians().sin())
+        };
+        let rise = time_of(next_rise(wave, midnight, 0.0, Duration::days(1)));
+        assert!((rise - (midnight + Duration::hours(6))).num_seconds().abs() <= 1);
+        let set = time_of(next_set(wave, midnight, 0.0, Duration::days(1)));
+        assert!((set - (midnight + Duration::hours(18))).num_seconds().abs() <= 1);
+
+        // too short a window to reach the rise at 06:00, starting below the horizon
+        assert_eq!(
+            next_rise(wave, midnight, 0.0, Duration::hours(5)),
+            RiseSet::NeverRises
+        );
+        // or the set at 18:00, starting above it
+        let noon = midnight + Duration::hours(12);
+        assert_eq!(
+            next_set(wave, noon, 0.0, Duration::hours(5)),
+            RiseSet::Circumpolar
+        );
+
+        let gap = |time: DateTime<Utc>| {
+            if time < midnight + Duration::hours(3) {
+                wave(time)
+            } else {
+                None
+            }
+        };
+        assert_eq!(
+            next_rise(gap, midnight, 0.0, Duration::days(1)),
+            RiseSet::NoEphemeris
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::nutation::{nutation, true_obliquity};
+use crate::ra_dec_calculations::{
+    calculate_days_since_j2000, calculate_local_sidereal_time, calculate_ut1_days_since_j2000,
+};
+use crate::time_scales::{self, TimeScale};
+use crate::units::ARCSEC_TO_DEG;
+use chrono::{DateTime, Utc};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SiderealModel {
+    // the low precision formula from the linked article, good to roughly an arcminute
+    Approximate,
+    // mean sidereal time as a polynomial in UT (Meeus 12.4)
+    Iau1982Mean,
+    // mean sidereal time built on the Earth rotation angle (Capitaine et al. 2003)
+    #[default]
+    Iau2006Mean,
+    // IAU 2006 mean sidereal time corrected by the equation of the equinoxes
+    Iau2006Apparent,
+}
+
+fn julian_centuries(days_j2000: f64) -> f64 {
+    days_j2000 / 36525.0
+}
+
+// Earth rotation angle in degrees, IERS Conventions 2010 eq. 5.15
+// `ut1_utc` in seconds throughout this module, None to take UT1 as UTC
+pub fn earth_rotation_angle(time: DateTime<Utc>, ut1_utc: Option<f64>) -> f64 {
+    let du = calculate_ut1_days_since_j2000(time, ut1_utc);
+    // splitting off the whole days keeps the large multiplier from eating precision
+    let fraction = du.rem_euclid(1.0);
+    let turns = fraction + 0.779_057_273_264_0 + 0.002_737_811_911_354_48 * du;
+    turns.rem_euclid(1.0) * 360.0
+}
+
+pub fn gmst_1982(time: DateTime<Utc>, ut1_utc: Option<f64>) -> f64 {
+    let d = calculate_ut1_days_since_j2000(time, ut1_utc);
+    let t = julian_centuries(d);
+    let gmst =
+        280.460_618_37 + 360.985_647_366_29 * d + 0.000_387_933 * t * t - t * t * t / 38_710_000.0;
+    gmst.rem_euclid(360.0)
+}
+
+// the polynomial part runs on TT, the rotation on UT1
+pub fn gmst_2006(time: DateTime<Utc>, ut1_utc: Option<f64>) -> f64 {
+    let t = julian_centuries(calculate_days_since_j2000(time));
+    let polynomial = 0.014_506
+        + t * (4_612.156_534
+            + t * (1.391_581_7
+                + t * (-0.000_000_44 + t * (-0.000_029_956 + t * -0.000_000_036_8))));
+    (earth_rotation_angle(time, ut1_utc) + polynomial * ARCSEC_TO_DEG).rem_euclid(360.0)
+}
+
+// equation of the equinoxes in degrees including the two largest complementary terms
+pub fn equation_of_equinoxes(time: DateTime<Utc>) -> f64 {
+    let t = julian_centuries(calculate_days_since_j2000(time));
+    let omega = (125.044_52 - 1_934.136_261 * t).to_radians();
+    let (d_psi, _) = nutation(time);
+    d_psi * true_obliquity(time).to_radians().cos()
+        + (0.002_64 * omega.sin() + 0.000_063 * (2.0 * omega).sin()) * ARCSEC_TO_DEG
+}
+
+pub fn gast_2006(time: DateTime<Utc>, ut1_utc: Option<f64>) -> f64 {
+    (gmst_2006(time, ut1_utc) + equation_of_equinoxes(time)).rem_euclid(360.0)
+}
+
+pub fn greenwich_sidereal_time(
+    time: DateTime<Utc>,
+    model: SiderealModel,
+    ut1_utc: Option<f64>,
+) -> f64 {
+    match model {
+        SiderealModel::Approximate => {
+            // UT1−UTC is under a second, so at chrono's very limits UTC stands in for UT1
+            let ut1 = time_scales::reading(time, TimeScale::UT1, ut1_utc).unwrap_or(time);
+            calculate_local_sidereal_time(calculate_ut1_days_since_j2000(time, ut1_utc), 0.0, ut1)
+        }
+        SiderealModel::Iau1982Mean => gmst_1982(time, ut1_utc),
+        SiderealModel::Iau2006Mean => gmst_2006(time, ut1_utc),
+        SiderealModel::Iau2006Apparent => gast_2006(time, ut1_utc),
+    }
+}
+
+// local sidereal time in degrees for an east-positive longitude
+pub fn local_sidereal_time(
+    time: DateTime<Utc>,
+    long: f64,
+    model: SiderealModel,
+    ut1_utc: Option<f64>,
+) -> f64 {
+    (greenwich_sidereal_time(time, model, ut1_utc) + long).rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    // 1987 April 10 0h UT, Meeus example 12.a
+    fn meeus_12a() -> DateTime<Utc> {
+        Utc.timestamp_opt(545_011_200, 0).unwrap()
+    }
+
+    fn assert_degrees(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{} differs from {} by {}″",
+            actual,
+            expected,
+            (actual - expected) * 3600.0
+        );
+    }
+
+    #[test]
+    fn mean_sidereal_time_matches_meeus() {
+        // 13h 10m 46.3668s. Meeus uses the 1982 expression; the 2006 one carries the
+        // revised precession rate and drifts from it by about 0.05″ by 1987.
+        assert_degrees(gmst_1982(meeus_12a(), None), 197.693_195, 1e-6);
+        assert_degrees(gmst_2006(meeus_12a(), None), 197.693_195, 3e-5);
+        // 1987 April 10 19:21:00 UT, example 12.b: 8h 34m 57.0896s
+        let evening = meeus_12a() + Duration::seconds(19 * 3600 + 21 * 60);
+        assert_degrees(gmst_1982(evening, None), 128.737_873_4, 1e-6);
+        assert_degrees(gmst_2006(evening, None), 128.737_873_4, 3e-5);
+    }
+
+    #[test]
+    fn mean_sidereal_time_at_j2000() {
+        // 2000 January 1 12h UT1
+        let j2000 = Utc.timestamp_opt(946_728_000, 0).unwrap();
+        assert_degrees(gmst_1982(j2000, None), 280.460_618_37, 1e-8);
+        // the 2006 expression adds a 0.014506″ constant to the rotation angle
+        let expected_2006 = 280.460_618_37 + 0.014_506 / 3600.0;
+        assert_degrees(gmst_2006(j2000, None), expected_2006, 1e-7);
+    }
+
+    #[test]
+    fn apparent_sidereal_time_matches_meeus() {
+        // 13h 10m 46.1351s, the mean value corrected by Δψ = −3.788″
+        assert_degrees(gast_2006(meeus_12a(), None), 197.692_229_6, 3e-5);
+        assert_degrees(equation_of_equinoxes(meeus_12a()) * 240.0, -0.231_7, 1e-3);
+    }
+
+    #[test]
+    fn ut1_offset_moves_sidereal_time() {
+        let shifted = gmst_2006(meeus_12a(), Some(0.5));
+        let later = gmst_2006(meeus_12a() + Duration::milliseconds(500), None);
+        assert_degrees(shifted, later, 1e-9);
+    }
+
+    #[test]
+    fn approximate_formula_uses_seconds_and_nanoseconds() {
+        let time = meeus_12a() + Duration::hours(3);
+        let days = calculate_days_since_j2000(time);
+        let base = calculate_local_sidereal_time(days, 0.0, time);
+        let seconds = calculate_local_sidereal_time(days, 0.0, time + Duration::seconds(30));
+        let nanos =
+            calculate_local_sidereal_time(days, 0.0, time + Duration::nanoseconds(500_000_000));
+        // UT enters at 15° per hour, the day count is held fixed here
+        assert_degrees(seconds - base, 0.125, 1e-9);
+        assert_degrees(nanos - base, 0.5 * 15.0 / 3600.0, 1e-9);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::units::{AU_KM, JULIAN_YEAR, MAS_TO_RAD, SECONDS_PER_DAY};
+use crate::vector;
+use crate::EquatorialCoords;
+use chrono::{DateTime, Utc};
+
+// one km/s in AU per Julian year
+const KM_S_TO_AU_YEAR: f64 = JULIAN_YEAR * SECONDS_PER_DAY / AU_KM;
+
+// catalog space motion of a star. Proper motion in RA is the on-sky rate, i.e. already
+// multiplied by cos δ as Hipparcos and Gaia list it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SpaceMotion {
+    // mas per Julian year
+    pub pm_ra_cos_dec: f64,
+    pub pm_dec: f64,
+    // mas, zero when unknown
+    pub parallax: f64,
+    // km/s, positive when receding
+    pub radial_velocity: f64,
+}
+
+impl SpaceMotion {
+    pub fn is_zero(&self) -> bool {
+        self.pm_ra_cos_dec == 0.0 && self.pm_dec == 0.0 && self.radial_velocity == 0.0
+    }
+
+    // moves a catalog position along a straight line in space from `from` to `to`. Working
+    // in units of the star's distance, the radial velocity only adds the perspective change
+    // of the proper motion, which is why it is dropped without a parallax.
+    pub fn propagate(
+        &self,
+        coords: EquatorialCoords,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> EquatorialCoords {
+        if self.is_zero() {
+            return coords;
+        }
+        let years = (to - from).num_milliseconds() as f64 / 1000.0 / SECONDS_PER_DAY / JULIAN_YEAR;
+
+        let p = vector::from_spherical(coords.ra, coords.dec);
+        let (sin_a, cos_a) = coords.ra.to_radians().sin_cos();
+        let (sin_d, cos_d) = coords.dec.to_radians().sin_cos();
+        let east = [-sin_a, cos_a, 0.0];
+        let north = [-sin_d * cos_a, -sin_d * sin_a, cos_d];
+        let radial = self.radial_velocity * KM_S_TO_AU_YEAR * self.parallax.max(0.0) * MAS_TO_RAD;
+
+        let mut velocity = vector::add_scaled([0.0; 3], east, self.pm_ra_cos_dec * MAS_TO_RAD);
+        velocity = vector::add_scaled(velocity, north, self.pm_dec * MAS_TO_RAD);
+        velocity = vector::add_scaled(velocity, p, radial);
+
+        let (ra, dec) = vector::to_spherical(vector::add_scaled(p, velocity, years));
+        EquatorialCoords { ra, dec }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::astro::astro_obj::AstroObject;
+    use crate::precession::Epoch;
+    use crate::ra_dec_calculations::angular_separation;
+    use chrono::{Duration, TimeZone};
+
+    // Barnard's Star, ICRS at J2000 (SIMBAD, from Gaia)
+    const BARNARD: EquatorialCoords = EquatorialCoords {
+        ra: 269.452_075_1,
+        dec: 4.693_391_0,
+    };
+
+    fn barnard_motion() -> SpaceMotion {
+        SpaceMotion {
+            pm_ra_cos_dec: -798.58,
+            pm_dec: 10_328.12,
+            parallax: 548.31,
+            radial_velocity: -110.6,
+        }
+    }
+
+    fn j2000() -> DateTime<Utc> {
+        Epoch::J2000.instant().unwrap()
+    }
+
+    fn years(count: i64) -> Duration {
+        Duration::milliseconds((count as f64 * JULIAN_YEAR * SECONDS_PER_DAY * 1000.0) as i64)
+    }
+
+    fn propagate_for(motion: SpaceMotion, count: i64) -> EquatorialCoords {
+        motion.propagate(BARNARD, j2000(), j2000() + years(count))
+    }
+
+    fn later_date() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_780_000_000, 0).unwrap()
+    }
+
+    #[test]
+    fn barnards_star_over_a_century() {
+        let later = propagate_for(barnard_motion(), 100);
+        // mostly north at 10.33″ a year
+        let north = (later.dec - BARNARD.dec) * 3600.0;
+        let east = (later.ra - BARNARD.ra) * 3600.0 * BARNARD.dec.to_radians().cos();
+        assert!((north - 1_032.8).abs() < 10.0, "{}", north);
+        assert!((east + 79.9).abs() < 1.0, "{}", east);
+
+        // the star is approaching, so its proper motion grows by dμ/dt = −2μ v_r π / A, about
+        // 1.29 mas a year², which adds ½ · 1.29 mas · 100² ≈ 6.4″ over the century
+        let total = angular_separation(BARNARD, later) * 3600.0;
+        let linear = (798.58f64.hypot(10_328.12)) * 100.0 / 1000.0;
+        assert!((total - linear - 6.4).abs() < 0.3, "{}", total - linear);
+
+        // without a parallax the radial velocity can't be used and the motion stays linear
+        let no_parallax = SpaceMotion {
+            parallax: 0.0,
+            ..barnard_motion()
+        };
+        let linear_only = angular_separation(BARNARD, propagate_for(no_parallax, 100)) * 3600.0;
+        assert!(
+            (linear_only - linear).abs() < 0.05,
+            "{}",
+            linear_only - linear
+        );
+    }
+
+    #[test]
+    fn stars_without_motion_stay_put() {
+        let still = SpaceMotion {
+            parallax: 548.31,
+            ..SpaceMotion::default()
+        };
+        assert_eq!(still.propagate(BARNARD, j2000(), later_date()), BARNARD);
+        // and time runs both ways
+        let earlier = propagate_for(barnard_motion(), -50);
+        assert!(((earlier.dec - BARNARD.dec) * 3600.0 + 516.4).abs() < 5.0);
+    }
+
+    #[test]
+    fn astro_objects_move_from_their_catalog_epoch() {
+        let star = AstroObject::new("Barnard's Star", BARNARD.ra, BARNARD.dec)
+            .with_proper_motion(-798.58, 10_328.12)
+            .with_parallax(548.31)
+            .with_radial_velocity(-110.6);
+        let expected = barnard_motion().propagate(BARNARD, j2000(), later_date());
+        assert_eq!(star.position_at(later_date()), expected);
+
+        // Gaia-style: J2000 frame but positions valid at J2016.0
+        let j2016 = j2000() + years(16);
+        let gaia = star.clone().with_catalog_epoch(j2016);
+        assert_eq!(gaia.position_at(j2016), BARNARD);
+
+        // coordinates of date have no instant of their own, so nothing moves until a catalog
+        // epoch is given
+        let of_date = star.with_epoch(Epoch::OfDate);
+        assert_eq!(of_date.position_at(later_date()), BARNARD);
+        let dated = of_date.with_catalog_epoch(j2000());
+        assert_eq!(dated.position_at(later_date()), expected);
+    }
+}
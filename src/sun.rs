@@ -0,0 +1,259 @@
+use crate::body::Body;
+use crate::ecliptic::ecliptic_to_equatorial;
+use crate::nutation::true_obliquity;
+use crate::observer::Observer;
+use crate::precession::mean_obliquity;
+use crate::ra_dec_calculations::{calculate_days_since_j2000, Accuracy, CalculationOptions};
+use crate::rise_set::{self, RiseSet};
+use crate::{EclipticCoords, EquatorialCoords};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+// geometric elements of the Sun's apparent geocentric orbit referred to the mean equinox
+// of date, angles in degrees and distance in AU
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolarOrbit {
+    pub true_longitude: f64,
+    pub mean_anomaly: f64,
+    pub eccentricity: f64,
+    pub perihelion: f64,
+    pub distance: f64,
+}
+
+// low precision solar theory from Meeus ch. 25, good to about 0.01°
+pub fn solar_orbit(time: DateTime<Utc>) -> SolarOrbit {
+    let t = calculate_days_since_j2000(time) / 36_525.0;
+    let mean_longitude = 280.466_46 + t * (36_000.769_83 + t * 0.000_303_2);
+    let mean_anomaly = 357.529_11 + t * (35_999.050_29 - t * 0.000_153_7);
+    let eccentricity = 0.016_708_634 - t * (0.000_042_037 + t * 0.000_000_126_7);
+    let m = mean_anomaly.to_radians();
+    let center = (1.914_602 - t * (0.004_817 + t * 0.000_014)) * m.sin()
+        + (0.019_993 - t * 0.000_101) * (2.0 * m).sin()
+        + 0.000_289 * (3.0 * m).sin();
+    let true_anomaly = (mean_anomaly + center).to_radians();
+
+    SolarOrbit {
+        true_longitude: (mean_longitude + center).rem_euclid(360.0),
+        mean_anomaly: mean_anomaly.rem_euclid(360.0),
+        eccentricity,
+        perihelion: 102.937_35 + t * (1.719_46 + t * 0.000_46),
+        distance: 1.000_001_018 * (1.0 - eccentricity * eccentricity)
+            / (1.0 + eccentricity * true_anomaly.cos()),
+    }
+}
+
+// altitude of the Sun's centre at sunrise and sunset: 34′ of refraction plus a 16′ semi-diameter
+pub const SUNRISE_ALTITUDE: f64 = -0.8333;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Twilight {
+    Civil,
+    Nautical,
+    Astronomical,
+}
+
+impl Twilight {
+    // solar altitude in degrees at which this twilight begins in the morning and ends at night
+    pub fn altitude(&self) -> f64 {
+        match self {
+            Twilight::Civil => -6.0,
+            Twilight::Nautical => -12.0,
+            Twilight::Astronomical => -18.0,
+        }
+    }
+}
+
+// the Sun as a built-in body; its position is geocentric, mean or apparent of date
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sun;
+
+impl Sun {
+    // apparent ecliptic longitude in degrees, corrected for nutation and aberration
+    pub fn apparent_longitude(&self, time: DateTime<Utc>) -> f64 {
+        let t = calculate_days_since_j2000(time) / 36_525.0;
+        let omega = (125.04 - 1_934.136 * t).to_radians();
+        solar_orbit(time).true_longitude - 0.005_69 - 0.004_78 * omega.sin()
+    }
+
+    pub fn coords_of_date(&self, time: DateTime<Utc>) -> EquatorialCoords {
+        let (sin_l, cos_l) = self.apparent_longitude(time).to_radians().sin_cos();
+        let (sin_e, cos_e) = true_obliquity(time).to_radians().sin_cos();
+        let ra = (cos_e * sin_l).atan2(cos_l).to_degrees().rem_euclid(360.0);
+        let dec = (sin_e * sin_l).asin().to_degrees();
+        EquatorialCoords { ra, dec }
+    }
+
+    // geometric position referred to the mean equator and equinox of date, without nutation
+    // or aberration; the frame `Accuracy::Standard` works in
+    pub fn mean_coords_of_date(&self, time: DateTime<Utc>) -> EquatorialCoords {
+        let ecliptic = EclipticCoords {
+            longitude: solar_orbit(time).true_longitude,
+            latitude: 0.0,
+        };
+        ecliptic_to_equatorial(ecliptic, mean_obliquity(time))
+    }
+
+    // whether the Sun is below the altitude where the given twilight ends
+    pub fn is_dark(&self, observer: &Observer, time: DateTime<Utc>, twilight: Twilight) -> bool {
+        let options = CalculationOptions::default();
+        matches!(
+            self.coords_as_alt_az_with(observer, time, options),
+            Some(horizontal) if horizontal.altitude < twilight.altitude()
+        )
+    }
+}
+
+impl Body for Sun {
+    fn name(&self) -> &str {
+        "Sun"
+    }
+
+    fn coords_seen_from(
+        &self,
+        _observer: &Observer,
+        time: DateTime<Utc>,
+        options: CalculationOptions,
+    ) -> Option<EquatorialCoords> {
+        Some(match options.accuracy {
+            Accuracy::Standard => self.mean_coords_of_date(time),
+            Accuracy::High => self.coords_of_date(time),
+        })
+    }
+
+    // long enough to see the end of the polar night
+    fn search_window(&self) -> Duration {
+        Duration::days(366)
+    }
+}
+
+// the instant the Sun crosses `altitude` going up (or down) during the observer's local date.
+// When it doesn't cross that day the result says whether it stayed above or below.
+fn crossing_on(observer: &Observer, date: NaiveDate, altitude: f64, rising: bool) -> RiseSet {
+    let midnight = local_midnight(observer, date);
+    // 23 or 25 hours when the clocks change that day
+    let day = match date.succ_opt() {
+        Some(next) => local_midnight(observer, next) - midnight,
+        None => Duration::days(1),
+    };
+    // geometric, as the altitudes of the events are
+    let options = CalculationOptions {
+        refraction: None,
+        ..CalculationOptions::default()
+    };
+    let altitude_at = |time| {
+        Sun.coords_as_alt_az_with(observer, time, options)
+            .map(|horizontal| horizontal.altitude)
+    };
+    if rising {
+        rise_set::next_rise(altitude_at, midnight, altitude, day)
+    } else {
+        rise_set::next_set(altitude_at, midnight, altitude, day)
+    }
+}
+
+// the start of `date` on the observer's clock. The offset is looked up twice so it is the one in
+// force at local midnight rather than at midnight UTC
+fn local_midnight(observer: &Observer, date: NaiveDate) -> DateTime<Utc> {
+    let naive = date.and_hms_opt(0, 0, 0).unwrap();
+    let mut midnight = Utc.from_utc_datetime(&naive);
+    for _ in 0..2 {
+        let offset = observer.timezone.offset_at(midnight).local_minus_utc();
+        midnight = Utc.from_utc_datetime(&naive) - Duration::seconds(offset as i64);
+    }
+    midnight
+}
+
+pub fn sunrise(observer: &Observer, date: NaiveDate) -> RiseSet {
+    crossing_on(observer, date, SUNRISE_ALTITUDE, true)
+}
+
+pub fn sunset(observer: &Observer, date: NaiveDate) -> RiseSet {
+    crossing_on(observer, date, SUNRISE_ALTITUDE, false)
+}
+
+// morning twilight begins when the Sun climbs through the twilight altitude
+pub fn twilight_begins(observer: &Observer, date: NaiveDate, twilight: Twilight) -> RiseSet {
+    crossing_on(observer, date, twilight.altitude(), true)
+}
+
+// evening twilight ends, e.g. astronomical dark starts, when the Sun sinks through it
+pub fn twilight_ends(observer: &Observer, date: NaiveDate, twilight: Twilight) -> RiseSet {
+    crossing_on(observer, date, twilight.altitude(), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer::tests::los_angeles_spring_2025;
+    use crate::GeoCoords;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn accuracy_selects_mean_or_apparent_place() {
+        let observer = Observer::new(GeoCoords::from_east_longitude(0.0, 0.0).unwrap());
+        let time = Utc.timestamp_opt(946_728_000, 0).unwrap();
+        let standard = CalculationOptions::default();
+        let high = CalculationOptions {
+            accuracy: Accuracy::High,
+            ..standard
+        };
+        let mean = Sun.coords_seen_from(&observer, time, standard);
+        let apparent = Sun.coords_seen_from(&observer, time, high);
+        assert_eq!(mean, Some(Sun.mean_coords_of_date(time)));
+        assert_eq!(apparent, Some(Sun.coords_of_date(time)));
+        let (mean, apparent) = (mean.unwrap(), apparent.unwrap());
+        // aberration and nutation in longitude together move the Sun by about 35″ at J2000
+        let shift = (apparent.ra - mean.ra) * 3600.0;
+        assert!(shift < -20.0 && shift > -45.0, "{}", shift);
+    }
+
+    #[test]
+    fn polar_days_and_nights_have_no_sunrise_or_sunset() {
+        let longyearbyen = Observer::new(GeoCoords::from_east_longitude(78.22, 15.63).unwrap());
+        assert_eq!(
+            sunrise(&longyearbyen, date(2025, 1, 1)),
+            RiseSet::NeverRises
+        );
+        assert_eq!(
+            sunset(&longyearbyen, date(2025, 6, 21)),
+            RiseSet::Circumpolar
+        );
+
+        // the Body search looks far enough ahead to find the end of the polar night
+        let new_year = Utc.timestamp_opt(1_735_689_600, 0).unwrap();
+        let options = CalculationOptions::default();
+        match Sun.next_rise(&longyearbyen, new_year, SUNRISE_ALTITUDE, options) {
+            RiseSet::Time(time) => assert!(time - new_year > Duration::days(30), "{}", time),
+            event => panic!("expected a sunrise, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn sunrise_falls_on_the_requested_date() {
+        let zone = los_angeles_spring_2025();
+        let los_angeles = Observer::new(GeoCoords::from_west_longitude(34.05, 118.24).unwrap())
+            .with_timezone(&zone);
+        match sunrise(&los_angeles, date(2025, 1, 1)) {
+            RiseSet::Time(time) => {
+                let local = los_angeles.local_time(time);
+                assert_eq!(local.naive_local().date(), date(2025, 1, 1));
+                assert_eq!(local.format("%H").to_string(), "06");
+            }
+            event => panic!("expected a sunrise, got {:?}", event),
+        }
+
+        // the clocks go forward at 2am that morning, and stay forward for the summer
+        for &(day, hour) in &[(date(2025, 3, 9), "07"), (date(2025, 7, 1), "05")] {
+            match sunrise(&los_angeles, day) {
+                RiseSet::Time(time) => {
+                    let local = los_angeles.local_time(time);
+                    assert_eq!(local.naive_local().date(), day);
+                    assert_eq!(local.format("%H").to_string(), hour, "{}", local);
+                }
+                event => panic!("expected a sunrise, got {:?}", event),
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use crate::julian::JulianDate;
+use crate::units::SECONDS_PER_DAY;
+use chrono::{DateTime, Datelike, Duration, Utc};
+
+// TT runs a fixed 32.184 s ahead of TAI
+const TT_MINUS_TAI: f64 = 32.184;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeScale {
+    UTC,
+    TAI,
+    // Terrestrial Time, the argument of the precession, nutation and ephemeris series
+    TT,
+    // the Earth's rotation angle as a time, drives sidereal time
+    UT1,
+    // Barycentric Dynamical Time, within 2 ms of TT
+    TDB,
+}
+
+// (Unix time of 00:00 UTC on the first of the month, TAI−UTC in seconds from then),
+// IERS Bulletin C. Kept as timestamps since this is looked up for every position computed.
+// Extend when a new leap second is announced.
+const LEAP_SECONDS: [(i64, f64); 28] = [
+    (63072000, 10.0),   // 1972-01-01
+    (78796800, 11.0),   // 1972-07-01
+    (94694400, 12.0),   // 1973-01-01
+    (126230400, 13.0),  // 1974-01-01
+    (157766400, 14.0),  // 1975-01-01
+    (189302400, 15.0),  // 1976-01-01
+    (220924800, 16.0),  // 1977-01-01
+    (252460800, 17.0),  // 1978-01-01
+    (283996800, 18.0),  // 1979-01-01
+    (315532800, 19.0),  // 1980-01-01
+    (362793600, 20.0),  // 1981-07-01
+    (394329600, 21.0),  // 1982-07-01
+    (425865600, 22.0),  // 1983-07-01
+    (489024000, 23.0),  // 1985-07-01
+    (567993600, 24.0),  // 1988-01-01
+    (631152000, 25.0),  // 1990-01-01
+    (662688000, 26.0),  // 1991-01-01
+    (709948800, 27.0),  // 1992-07-01
+    (741484800, 28.0),  // 1993-07-01
+    (773020800, 29.0),  // 1994-07-01
+    (820454400, 30.0),  // 1996-01-01
+    (867715200, 31.0),  // 1997-07-01
+    (915148800, 32.0),  // 1999-01-01
+    (1136073600, 33.0), // 2006-01-01
+    (1230768000, 34.0), // 2009-01-01
+    (1341100800, 35.0), // 2012-07-01
+    (1435708800, 36.0), // 2015-07-01
+    (1483228800, 37.0), // 2017-01-01
+];
+
+// TAI−UTC in seconds. Before 1972 UTC was steered to stay near UT1, so it is treated as UT1
+// and the offset follows the ΔT model instead of the old fractional-second steps.
+pub fn tai_minus_utc(time: DateTime<Utc>) -> f64 {
+    let timestamp = time.timestamp();
+    if timestamp < LEAP_SECONDS[0].0 {
+        return delta_t_model(time) - TT_MINUS_TAI;
+    }
+    let index = LEAP_SECONDS.partition_point(|&(start, _)| start <= timestamp);
+    LEAP_SECONDS[index - 1].1
+}
+
+pub fn tt_minus_utc(time: DateTime<Utc>) -> f64 {
+    tai_minus_utc(time) + TT_MINUS_TAI
+}
+
+// ΔT = TT−UT1 in seconds from the Espenak & Meeus (2006) polynomial fits, good to about a
+// second for recent centuries and increasingly uncertain further away
+pub fn delta_t_model(time: DateTime<Utc>) -> f64 {
+    let y = time.year() as f64 + (time.month() as f64 - 0.5) / 12.0;
+    let long_term = |y: f64| {
+        let u = (y - 1820.0) / 100.0;
+        -20.0 + 32.0 * u * u
+    };
+    match y {
+        y if y < -500.0 => long_term(y),
+        y if y < 500.0 => {
+            let u = y / 100.0;
+            10_583.6
+                + u * (-1_014.41
+                    + u * (33.783_11
+                        + u * (-5.952_053
+                            + u * (-0.179_845_2 + u * (0.022_174_192 + u * 0.009_031_652_1)))))
+        }
+        y if y < 1600.0 => {
+            let u = (y - 1000.0) / 100.0;
+            1_574.2
+                + u * (-556.01
+                    + u * (71.234_72
+                        + u * (0.319_781
+                            + u * (-0.850_346_3 + u * (-0.005_050_998 + u * 0.008_357_207_3)))))
+        }
+        y if y < 1700.0 => {
+            let t = y - 1600.0;
+            120.0 + t * (-0.980_8 + t * (-0.015_32 + t / 7_129.0))
+        }
+        y if y < 1800.0 => {
+            let t = y - 1700.0;
+            8.83 + t * (0.160_3 + t * (-0.005_928_5 + t * (0.000_133_36 - t / 1_174_000.0)))
+        }
+        y if y < 1860.0 => {
+            let t = y - 1800.0;
+            13.72
+                + t * (-0.332_447
+                    + t * (0.006_861_2
+                        + t * (0.004_111_6
+                            + t * (-0.000_374_36
+                                + t * (0.000_012_127_2
+                                    + t * (-0.000_000_169_9 + t * 0.000_000_000_875))))))
+        }
+        y if y < 1900.0 => {
+            let t = y - 1860.0;
+            7.62 + t
+                * (0.573_7
+                    + t * (-0.251_754
+                        + t * (0.016_806_68 + t * (-0.000_447_362_4 + t / 233_174.0))))
+        }
+        y if y < 1920.0 => {
+            let t = y - 1900.0;
+            -2.79 + t * (1.494_119 + t * (-0.059_893_9 + t * (0.006_196_6 - t * 0.000_197)))
+        }
+        y if y < 1941.0 => {
+            let t = y - 1920.0;
+            21.20 + t * (0.844_93 + t * (-0.076_100 + t * 0.002_093_6))
+        }
+        y if y < 1961.0 => {
+            let t = y - 1950.0;
+            29.07 + t * (0.407 + t * (-1.0 / 233.0 + t / 2_547.0))
+        }
+        y if y < 1986.0 => {
+            let t = y - 1975.0;
+            45.45 + t * (1.067 + t * (-1.0 / 260.0 - t / 718.0))
+        }
+        y if y < 2005.0 => {
+            let t = y - 2000.0;
+            63.86
+                + t * (0.334_5
+                    + t * (-0.060_374
+                        + t * (0.001_727_5 + t * (0.000_651_814 + t * 0.000_023_735_99))))
+        }
+        y if y < 2050.0 => {
+            let t = y - 2000.0;
+            62.92 + t * (0.322_17 + t * 0.005_589)
+        }
+        y if y < 2150.0 => long_term(y) - 0.562_8 * (2150.0 - y),
+        y => long_term(y),
+    }
+}
+
+// UT1−UTC in seconds. A supplied value (e.g. from IERS Bulletin A) wins; otherwise it is
+// taken as zero, since leap seconds keep UTC within 0.9 s of UT1 and earlier UTC is read as
+// UT1 anyway (see `tai_minus_utc`).
+pub fn ut1_minus_utc(supplied: Option<f64>) -> f64 {
+    supplied.unwrap_or(0.0)
+}
+
+// ΔT = TT−UT1 in seconds as used by the rest of the crate
+pub fn delta_t(time: DateTime<Utc>, ut1_utc: Option<f64>) -> f64 {
+    tt_minus_utc(time) - ut1_minus_utc(ut1_utc)
+}
+
+// TDB−TT in seconds, the leading periodic terms (Fairhead & Bretagnon 1990)
+pub fn tdb_minus_tt(time: DateTime<Utc>) -> f64 {
+    let days = utc_days_since_j2000(time);
+    let g = (357.53 + 0.985_600_28 * days).to_radians();
+    0.001_657 * g.sin() + 0.000_014 * (2.0 * g).sin()
+}
+
+// seconds to add to a UTC clock reading to get the reading of the given scale
+pub fn offset_from_utc(time: DateTime<Utc>, scale: TimeScale, ut1_utc: Option<f64>) -> f64 {
+    match scale {
+        TimeScale::UTC => 0.0,
+        TimeScale::TAI => tai_minus_utc(time),
+        TimeScale::TT => tt_minus_utc(time),
+        TimeScale::UT1 => ut1_minus_utc(ut1_utc),
+        TimeScale::TDB => tt_minus_utc(time) + tdb_minus_tt(time),
+    }
+}
+
+// the instant read off a clock running on the given scale. chrono has no notion of time
+// scales, so the result is only a label to format or feed back into `days_since_j2000`.
+// None when the offset carries the reading past what chrono can represent, which the ΔT model
+// does within a few years of its lower limit.
+pub fn reading(
+    time: DateTime<Utc>,
+    scale: TimeScale,
+    ut1_utc: Option<f64>,
+) -> Option<DateTime<Utc>> {
+    let offset = offset_from_utc(time, scale, ut1_utc);
+    time.checked_add_signed(Duration::nanoseconds((offset * 1e9).round() as i64))
+}
+
+// the UTC instant at which a clock on the given scale shows `reading`; the offsets change
+// slowly enough that one correction is exact except within seconds of a leap second. None
+// when that instant is outside chrono's range.
+pub fn from_reading(
+    reading: DateTime<Utc>,
+    scale: TimeScale,
+    ut1_utc: Option<f64>,
+) -> Option<DateTime<Utc>> {
+    let shift = |at: DateTime<Utc>| {
+        let offset = offset_from_utc(at, scale, ut1_utc);
+        reading.checked_sub_signed(Duration::nanoseconds((offset * 1e9).round() as i64))
+    };
+    shift(shift(reading)?)
+}
+
+fn utc_days_since_j2000(time: DateTime<Utc>) -> f64 {
+    JulianDate::from_datetime(time).days_since_j2000()
+}
+
+// days from JD 2451545.0 counted on the given scale, i.e. from 2000-01-01 12:00 as read off
+// that scale's own clock
+pub fn days_since_j2000(time: DateTime<Utc>, scale: TimeScale, ut1_utc: Option<f64>) -> f64 {
+    utc_days_since_j2000(time) + offset_from_utc(time, scale, ut1_utc) / SECONDS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(timestamp: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(timestamp, 0).unwrap()
+    }
+
+    #[test]
+    fn leap_seconds_step_at_the_start_of_the_month() {
+        // 2017-01-01 00:00
+        assert_eq!(tai_minus_utc(at(1_483_228_799)), 36.0);
+        assert_eq!(tai_minus_utc(at(1_483_228_800)), 37.0);
+        assert_eq!(tai_minus_utc(at(1_700_000_000)), 37.0);
+        // 1972-01-01 00:00
+        assert_eq!(tai_minus_utc(at(63_072_000)), 10.0);
+        assert_eq!(tt_minus_utc(at(1_483_228_800)), 69.184);
+    }
+
+    #[test]
+    fn delta_t_model_near_published_values() {
+        // ΔT was 63.8 s at the start of 2000 and 29.2 s in 1950
+        assert!((delta_t_model(at(946_684_800)) - 63.8).abs() < 0.2);
+        assert!((delta_t_model(at(-631_152_000)) - 29.2).abs() < 0.5);
+    }
+
+    #[test]
+    fn readings_round_trip() {
+        let time = at(1_748_736_000);
+        for &scale in &[
+            TimeScale::TAI,
+            TimeScale::TT,
+            TimeScale::UT1,
+            TimeScale::TDB,
+        ] {
+            let reading = reading(time, scale, Some(0.1)).unwrap();
+            assert_eq!(from_reading(reading, scale, Some(0.1)), Some(time));
+        }
+    }
+}
@@ -0,0 +1,9 @@
+// unit conversions and physical constants shared across the modules
+
+pub(crate) const ARCSEC_TO_DEG: f64 = 1.0 / 3600.0;
+pub(crate) const MAS_TO_RAD: f64 = std::f64::consts::PI / (180.0 * 3_600_000.0);
+pub(crate) const SECONDS_PER_DAY: f64 = 86_400.0;
+// Julian year in days
+pub(crate) const JULIAN_YEAR: f64 = 365.25;
+// astronomical unit in km, IAU 2012
+pub(crate) const AU_KM: f64 = 149_597_870.7;
@@ -0,0 +1,86 @@
+// small 3-vector helpers shared by the coordinate frame rotations
+
+pub(crate) type Vec3 = [f64; 3];
+pub(crate) type Mat3 = [[f64; 3]; 3];
+
+// unit vector for a longitude-like and latitude-like angle pair in degrees
+pub(crate) fn from_spherical(long: f64, lat: f64) -> Vec3 {
+    let (long, lat) = (long.to_radians(), lat.to_radians());
+    [lat.cos() * long.cos(), lat.cos() * long.sin(), lat.sin()]
+}
+
+// back to (longitude in [0, 360), latitude) in degrees
+pub(crate) fn to_spherical(v: Vec3) -> (f64, f64) {
+    let long = v[1].atan2(v[0]).to_degrees().rem_euclid(360.0);
+    let lat = v[2].atan2((v[0] * v[0] + v[1] * v[1]).sqrt()).to_degrees();
+    (long, lat)
+}
+
+pub(crate) fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn add_scaled(a: Vec3, b: Vec3, scale: f64) -> Vec3 {
+    [
+        a[0] + b[0] * scale,
+        a[1] + b[1] * scale,
+        a[2] + b[2] * scale,
+    ]
+}
+
+pub(crate) fn normalize(v: Vec3) -> Vec3 {
+    let norm = dot(v, v).sqrt();
+    [v[0] / norm, v[1] / norm, v[2] / norm]
+}
+
+pub(crate) fn mul_vec(m: &Mat3, v: Vec3) -> Vec3 {
+    let mut out = [0.0; 3];
+    for (row, value) in m.iter().zip(out.iter_mut()) {
+        *value = row[0] * v[0] + row[1] * v[1] + row[2] * v[2];
+    }
+    out
+}
+
+pub(crate) fn mul(a: &Mat3, b: &Mat3) -> Mat3 {
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            out[i][j] = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+pub(crate) fn transpose(m: &Mat3) -> Mat3 {
+    let mut out = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            out[i][j] = m[j][i];
+        }
+    }
+    out
+}
+
+// frame rotations by an angle in degrees, following the SOFA R1/R2/R3 sign convention
+pub(crate) fn rot_x(angle: f64) -> Mat3 {
+    let (s, c) = angle.to_radians().sin_cos();
+    [[1.0, 0.0, 0.0], [0.0, c, s], [0.0, -s, c]]
+}
+
+pub(crate) fn rot_y(angle: f64) -> Mat3 {
+    let (s, c) = angle.to_radians().sin_cos();
+    [[c, 0.0, -s], [0.0, 1.0, 0.0], [s, 0.0, c]]
+}
+
+pub(crate) fn rot_z(angle: f64) -> Mat3 {
+    let (s, c) = angle.to_radians().sin_cos();
+    [[c, s, 0.0], [-s, c, 0.0], [0.0, 0.0, 1.0]]
+}